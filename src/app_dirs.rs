@@ -0,0 +1,35 @@
+use std::path::PathBuf;
+
+// Where the app keeps its files under the user's data and config dirs
+const APP_DIR_NAME: &str = "fonv_cracker";
+
+fn non_empty_env_var(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+// Follows the XDG spec on unix and uses the roaming app data on windows
+fn user_dir(xdg_env_var: &str, home_fallback: &[&str]) -> Option<PathBuf> {
+    if let Some(xdg_dir) = non_empty_env_var(xdg_env_var) {
+        return Some(xdg_dir);
+    }
+
+    if cfg!(windows) {
+        if let Some(app_data) = non_empty_env_var("APPDATA") {
+            return Some(app_data);
+        }
+    }
+
+    non_empty_env_var("HOME").map(|home| home_fallback.iter().fold(home, |dir, sub| dir.join(sub)))
+}
+
+// For files the app writes itself, e.g. stats
+pub fn data_dir() -> Option<PathBuf> {
+    user_dir("XDG_DATA_HOME", &[".local", "share"]).map(|dir| dir.join(APP_DIR_NAME))
+}
+
+// For files the user writes to configure the app
+pub fn config_dir() -> Option<PathBuf> {
+    user_dir("XDG_CONFIG_HOME", &[".config"]).map(|dir| dir.join(APP_DIR_NAME))
+}
@@ -0,0 +1,1038 @@
+//! Generating the board for a game: the password, the decoy words and the hex dump they're hidden
+//! in. Everything random comes from the rng passed in so a seeded rng always generates the same
+//! board.
+
+use crate::decision_tree::build_decision_tree;
+use crate::dict::dict::{DictLoadErr, EnglishDictChunk};
+use crate::profile::{
+    DifficultyProfile, HDDEntry, MAX_DUMP_SIZE, MAX_PANE_HEIGHT, MAX_PANE_WIDTH, MAX_WORD_LEN,
+    MIN_PANE_HEIGHT, MIN_PANE_WIDTH,
+};
+use snm_rand_utils::range_rng::RangeRng;
+use std::path::{Path, PathBuf};
+
+/// The attempts the preset difficulties give
+pub const MAX_ATTEMPTS: usize = 4;
+
+/// The five difficulties from the game, each with a preset profile
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    VeryEasy,
+    Easy,
+    Average,
+    Hard,
+    VeryHard,
+    /// Played with a profile that doesn't match any of the others
+    Custom,
+}
+
+pub const ALL_DIFFICULTIES: [Difficulty; 6] = [
+    Difficulty::VeryEasy,
+    Difficulty::Easy,
+    Difficulty::Average,
+    Difficulty::Hard,
+    Difficulty::VeryHard,
+    Difficulty::Custom,
+];
+
+impl std::str::FromStr for Difficulty {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("VeryEasy") || s.eq_ignore_ascii_case("VE") {
+            return Ok(Difficulty::VeryEasy);
+        }
+
+        if s.eq_ignore_ascii_case("Easy") || s.eq_ignore_ascii_case("E") {
+            return Ok(Difficulty::Easy);
+        }
+
+        if s.eq_ignore_ascii_case("Average") || s.eq_ignore_ascii_case("A") {
+            return Ok(Difficulty::Average);
+        }
+
+        if s.eq_ignore_ascii_case("Hard") || s.eq_ignore_ascii_case("H") {
+            return Ok(Difficulty::Hard);
+        }
+
+        if s.eq_ignore_ascii_case("VeryHard") || s.eq_ignore_ascii_case("VH") {
+            return Ok(Difficulty::VeryHard);
+        }
+
+        if s.eq_ignore_ascii_case("Custom") || s.eq_ignore_ascii_case("C") {
+            return Ok(Difficulty::Custom);
+        }
+
+        Err("Invalid difficulty string")
+    }
+}
+
+/// Written in the same form FromStr accepts so difficulties can round trip through text
+impl std::fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            Difficulty::VeryEasy => "VeryEasy",
+            Difficulty::Easy => "Easy",
+            Difficulty::Average => "Average",
+            Difficulty::Hard => "Hard",
+            Difficulty::VeryHard => "VeryHard",
+            Difficulty::Custom => "Custom",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A run of noise on a single row wrapped in a matching pair of brackets, e.g. "<#$%>". Selecting
+/// one either removes a dud password or resets the attempts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BracketSequence {
+    /// Where the opening bracket sits in the hex dump
+    pub offset: usize,
+    /// The length including both brackets
+    pub len: usize,
+}
+
+pub const BRACKET_PAIRS: [(char, char); 4] = [('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')];
+
+/// Noise characters never include brackets so the only bracket pairs are the ones we place. Dots
+/// are left out too since they mark where a dud was removed.
+pub const GARBAGE_CHARS: &[u8] = b"#$%&'*+,-/:;=?@^_|";
+
+/// Picks the password and then fills in decoys from each distance bucket in turn until there are
+/// word_count words. The last bucket keeps going until there are enough.
+///
+/// Returns the words, password first, along with the password. A small enough dictionary may run
+/// out of words, leaving fewer than word_count.
+///
+/// # Panics
+///
+/// If hd_distribution is empty. [`DifficultyProfile::validate`] rules this out for a profile's
+/// distances.
+pub fn generate_words(
+    dict_chunk: &EnglishDictChunk,
+    hd_distribution: &[HDDEntry],
+    word_count: usize,
+    rng: &mut dyn RangeRng<usize>,
+) -> (Vec<String>, String) {
+    assert!(
+        !hd_distribution.is_empty(),
+        "generate_words needs at least one distance bucket"
+    );
+    let mut words = Vec::with_capacity(word_count);
+    let goal_word = dict_chunk.get_random_word(rng);
+    words.push(goal_word.clone());
+
+    let mut current_hd_distribution_index = 0;
+    let mut hd_distribution_tracker = hd_distribution.to_vec();
+    let mut hamming_distance_sorted_iter = dict_chunk.get_hamming_distance_sorted_words(&goal_word);
+
+    while words.len() < word_count {
+        let is_last_entry = current_hd_distribution_index == hd_distribution_tracker.len() - 1;
+        let current_hd_distribution_entry =
+            &mut hd_distribution_tracker[current_hd_distribution_index];
+
+        // words are sorted by distance so skip straight past the ones which are too similar
+        hamming_distance_sorted_iter
+            .skip_to_distance(current_hd_distribution_entry.hamming_distance);
+        let next_sorted_word_pair = hamming_distance_sorted_iter.next();
+        let word = match next_sorted_word_pair {
+            None => break, // we are out of words!
+            Some((word, _)) => word,
+        };
+
+        current_hd_distribution_entry.num_words =
+            current_hd_distribution_entry.num_words.saturating_sub(1);
+        words.push(String::from(word));
+
+        if current_hd_distribution_entry.num_words == 0 && !is_last_entry {
+            current_hd_distribution_index += 1;
+        }
+    }
+
+    (words, goal_word)
+}
+
+/// Shuffles the words so the password isn't always first
+pub fn simple_shuffle<T>(mut v: Vec<T>, rng: &mut dyn RangeRng<usize>) -> Vec<T> {
+    const NUM_SWAPS: usize = 100; // a good-enough heuristic for shuffling the words in place
+
+    for _ in 0..NUM_SWAPS {
+        let index = rng.gen_range(0, v.len());
+        v.swap(0, index);
+    }
+
+    v
+}
+
+/// Hides the words at random offsets in a string of noise target_size chars long. Returns the
+/// string along with each word's offset into it.
+///
+/// # Panics
+///
+/// If the words add up to more than target_size chars. [`DifficultyProfile::validate`] makes sure a
+/// profile's words fit in its hex dump.
+pub fn obfuscate_words(
+    words: &[String],
+    target_size: usize,
+    rng: &mut dyn RangeRng<usize>,
+) -> (String, Vec<usize>) {
+    let initial_length_from_words: usize = words.iter().fold(0, |acc, word| acc + word.len());
+    assert!(
+        initial_length_from_words <= target_size,
+        "{} chars of words don't fit in {} chars",
+        initial_length_from_words,
+        target_size
+    );
+    let remaining_char_count_to_generate = target_size - initial_length_from_words;
+
+    // place the words at offsets within the final obfuscated string such that filling in between/around
+    // those words will generate the final obfuscated string
+    let offsets = {
+        let mut offsets = Vec::new();
+        for word in words.iter() {
+            for offset in offsets.iter_mut() {
+                *offset += word.len();
+            }
+            offsets.insert(0, 0);
+        }
+
+        for _ in 0..remaining_char_count_to_generate {
+            // Increment each offset starting from a random offset.
+            // This simulates adding a character before a random word in the final obfuscated string
+            // e.g. if offsets_to_bump_start = 1, then a character is added between words 0 and 1
+            // and words 1->onward are then offset by an additional character.
+            let offsets_to_bump_start = rng.gen_range(0, offsets.len() + 1);
+
+            for offset in offsets.iter_mut().skip(offsets_to_bump_start) {
+                *offset += 1;
+            }
+        }
+        offsets
+    };
+
+    let mut string_builder = String::with_capacity(target_size);
+
+    // fill the string with the initial garbage chars
+    for _ in 0..remaining_char_count_to_generate {
+        let garbage_char = GARBAGE_CHARS[rng.gen_range(0, GARBAGE_CHARS.len())] as char;
+        string_builder.push(garbage_char);
+    }
+
+    // insert each of the offset words
+    for (word, offset) in words.iter().zip(offsets.iter()) {
+        string_builder.insert_str(*offset, word);
+    }
+
+    (string_builder, offsets)
+}
+
+/// Wraps random runs of noise in brackets. Each sequence sits on a single row and only covers
+/// noise so it never cuts into a word or another sequence. Fewer than max_count sequences may fit.
+pub fn insert_bracket_sequences(
+    hex_dump: &mut String,
+    row_width: usize,
+    max_count: usize,
+    rng: &mut dyn RangeRng<usize>,
+) -> Vec<BracketSequence> {
+    const MIN_LEN: usize = 2; // just the brackets
+    const MAX_LEN: usize = 6;
+    const PLACEMENT_TRIES: usize = 100;
+
+    let mut bracket_sequences = Vec::new();
+    for _ in 0..PLACEMENT_TRIES {
+        if bracket_sequences.len() == max_count {
+            break;
+        }
+
+        let len = rng.gen_range(MIN_LEN, MAX_LEN + 1);
+        let offset = rng.gen_range(0, hex_dump.len());
+        let fits_in_row = (offset % row_width) + len <= row_width;
+        if !fits_in_row || offset + len > hex_dump.len() {
+            continue;
+        }
+
+        // the inside of an earlier sequence is noise too so check it isn't being nested in one
+        let covers_only_noise = hex_dump[offset..offset + len]
+            .bytes()
+            .all(|b| GARBAGE_CHARS.contains(&b))
+            && bracket_sequences.iter().all(|other: &BracketSequence| {
+                offset + len <= other.offset || other.offset + other.len <= offset
+            });
+        if !covers_only_noise {
+            continue;
+        }
+
+        let (open, close) = BRACKET_PAIRS[rng.gen_range(0, BRACKET_PAIRS.len())];
+        hex_dump.replace_range(offset..offset + 1, &open.to_string());
+        hex_dump.replace_range(offset + len - 1..offset + len, &close.to_string());
+        bracket_sequences.push(BracketSequence { offset, len });
+    }
+
+    bracket_sequences
+}
+
+/// The size of each hex dump pane, in chars
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaneSize {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl PaneSize {
+    pub fn bytes(&self) -> usize {
+        self.width * self.height
+    }
+}
+
+/// The hex dump is split across two panes, side by side
+pub const NUM_PANES: usize = 2;
+
+// The hex dump's addresses start somewhere past here. Profiles can't make the dump big enough to
+// push it past 0xFFFF.
+const MIN_START_ADDR: usize = 0xCC00;
+const_assert!(MIN_START_ADDR + MAX_DUMP_SIZE < 0xFFFF);
+
+// Boards which don't meet the profile's solvability limits get regenerated up to this many times
+const MAX_GENERATE_TRIES: usize = 100;
+
+#[derive(Debug)]
+pub enum GenerateErr {
+    LoadDict(DictLoadErr),
+    SolvabilityNotMet { tries: usize },
+}
+
+impl std::fmt::Display for GenerateErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GenerateErr::LoadDict(err) => write!(f, "couldn't load the dictionary: {}", err),
+            GenerateErr::SolvabilityNotMet { tries } => write!(
+                f,
+                "none of the {} boards generated were within the profile's solvability limits",
+                tries
+            ),
+        }
+    }
+}
+
+// Bump the version whenever the board file format changes in a way older versions can't read
+const BOARD_HEADER_PREFIX: &str = "fonv_cracker board ";
+const BOARD_HEADER: &str = "fonv_cracker board v1";
+
+#[derive(Debug)]
+pub enum BoardErr {
+    ReadFile(PathBuf, std::io::Error),
+    WriteFile(PathBuf, std::io::Error),
+    MissingHeader,
+    UnsupportedVersion(String),
+    InvalidLine(usize, String),
+    MissingPane,
+    MissingStartAddr,
+    MissingSolution,
+    PaneOutOfRange(usize, usize),
+    WrongDumpSize { found: usize, expected: usize },
+    StartAddrOutOfRange(usize),
+    TooFewWords(usize),
+    InvalidWord(String),
+    WordNotAtOffset { word: String, offset: usize },
+    SolutionNotAWord(String),
+    InvalidBrackets(usize),
+}
+
+impl std::fmt::Display for BoardErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BoardErr::ReadFile(path, err) => {
+                write!(f, "couldn't read board {}: {}", path.display(), err)
+            }
+            BoardErr::WriteFile(path, err) => {
+                write!(f, "couldn't write board {}: {}", path.display(), err)
+            }
+            BoardErr::MissingHeader => write!(f, "the file doesn't start with '{}'", BOARD_HEADER),
+            BoardErr::UnsupportedVersion(version) => write!(
+                f,
+                "board version '{}' isn't supported, expected '{}'",
+                version, BOARD_HEADER
+            ),
+            BoardErr::InvalidLine(line_num, line) => {
+                write!(f, "invalid board line {}: '{}'", line_num, line)
+            }
+            BoardErr::MissingPane => write!(f, "the board has no pane size"),
+            BoardErr::MissingStartAddr => write!(f, "the board has no start address"),
+            BoardErr::MissingSolution => write!(f, "the board has no solution"),
+            BoardErr::PaneOutOfRange(width, height) => write!(
+                f,
+                "a {}x{} pane isn't between {}x{} and {}x{}",
+                width, height, MIN_PANE_WIDTH, MIN_PANE_HEIGHT, MAX_PANE_WIDTH, MAX_PANE_HEIGHT
+            ),
+            BoardErr::WrongDumpSize { found, expected } => write!(
+                f,
+                "the hex dump has {} characters but the panes hold {}",
+                found, expected
+            ),
+            BoardErr::StartAddrOutOfRange(start_addr) => write!(
+                f,
+                "the hex dump runs past 0xFFFF from start address {:#X}",
+                start_addr
+            ),
+            BoardErr::TooFewWords(word_count) => write!(
+                f,
+                "{} word(s) isn't enough, the password needs at least one decoy",
+                word_count
+            ),
+            BoardErr::InvalidWord(word) => write!(
+                f,
+                "'{}' has to be letters only, between 2 and {} long and as long as the others",
+                word, MAX_WORD_LEN
+            ),
+            BoardErr::WordNotAtOffset { word, offset } => write!(
+                f,
+                "'{}' isn't at offset {} or overlaps the word before it",
+                word, offset
+            ),
+            BoardErr::SolutionNotAWord(solution) => {
+                write!(f, "the solution '{}' isn't one of the words", solution)
+            }
+            BoardErr::InvalidBrackets(offset) => write!(
+                f,
+                "the brackets at offset {} aren't a matching pair on one row, clear of everything else",
+                offset
+            ),
+        }
+    }
+}
+
+fn worst_case_attempts(words: &[String]) -> usize {
+    // there are always words to build a tree from
+    build_decision_tree(words).unwrap().worst_case_attempts()
+}
+
+/// Everything generated for one game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board {
+    pub pane_size: PaneSize,
+    /// Both panes' worth of noise with the words and bracket sequences hidden in it
+    pub hex_dump: String,
+    /// The words in the order they appear in the hex dump, including the solution
+    pub words: Vec<String>,
+    /// Where each word starts in the hex dump
+    pub word_offsets: Vec<usize>,
+    pub solution: String,
+    pub bracket_sequences: Vec<BracketSequence>,
+    /// The memory address shown for the first char of the hex dump
+    pub start_addr: usize,
+}
+
+impl Board {
+    /// Generate a board for a profile, failing if the dictionary chunk doesn't have enough words.
+    /// When the profile limits how many attempts the board can take to solve, the words are
+    /// regenerated until they meet the limits or the tries run out.
+    pub fn generate(
+        profile: &DifficultyProfile,
+        dict_chunk: &EnglishDictChunk,
+        rng: &mut dyn RangeRng<usize>,
+    ) -> Result<Self, GenerateErr> {
+        Self::generate_measured(profile, dict_chunk, rng).map(|(board, _)| board)
+    }
+
+    /// Generate a board the same way as [`Board::generate`], also handing back its worst case
+    /// attempts if they had to be worked out to check the profile's solvability limits
+    pub fn generate_measured(
+        profile: &DifficultyProfile,
+        dict_chunk: &EnglishDictChunk,
+        rng: &mut dyn RangeRng<usize>,
+    ) -> Result<(Self, Option<usize>), GenerateErr> {
+        let has_solvability_limit =
+            profile.min_solvability.is_some() || profile.max_solvability.is_some();
+        let mut tries = 0;
+        let (words, solution, worst_case) = loop {
+            let (words, solution) = generate_words(
+                dict_chunk,
+                &profile.distance_buckets,
+                profile.word_count,
+                rng,
+            );
+            if words.len() < profile.word_count {
+                return Err(GenerateErr::LoadDict(DictLoadErr::NotEnoughWords {
+                    word_len: dict_chunk.word_len(),
+                    found: words.len(),
+                    required: profile.word_count,
+                }));
+            }
+            if !has_solvability_limit {
+                break (words, solution, None);
+            }
+
+            let worst_case = worst_case_attempts(&words);
+            // the minimum guarantees the board can be solved in time, the maximum that it's tricky
+            let too_hard = matches!(profile.min_solvability, Some(min) if worst_case > min);
+            let too_easy = matches!(profile.max_solvability, Some(max) if worst_case < max);
+            if !too_hard && !too_easy {
+                break (words, solution, Some(worst_case));
+            }
+
+            tries += 1;
+            if tries == MAX_GENERATE_TRIES {
+                return Err(GenerateErr::SolvabilityNotMet { tries });
+            }
+        };
+        let words = simple_shuffle(words, rng);
+
+        let pane_size = PaneSize {
+            width: profile.pane_width,
+            height: profile.pane_height,
+        };
+        let dump_size = pane_size.bytes() * NUM_PANES;
+        let (mut hex_dump, word_offsets) = obfuscate_words(&words, dump_size, rng);
+        let bracket_sequences = insert_bracket_sequences(
+            &mut hex_dump,
+            pane_size.width,
+            profile.bracket_sequences,
+            rng,
+        );
+
+        // For visual flair, randomize the mem address of the hex dump
+        let start_addr = rng.gen_range(MIN_START_ADDR, 0xFFFF - dump_size);
+
+        let board = Board {
+            pane_size,
+            hex_dump,
+            words,
+            word_offsets,
+            solution,
+            bracket_sequences,
+            start_addr,
+        };
+        Ok((board, worst_case))
+    }
+
+    /// The most attempts the best guessing strategy needs to find the solution among the words, the
+    /// same for every solution
+    pub fn worst_case_attempts(&self) -> usize {
+        worst_case_attempts(&self.words)
+    }
+
+    /// One field per line, e.g. "word 37 apple" for a word 37 chars into the hex dump, followed by
+    /// the hex dump itself one "row" line at a time, the first pane's rows before the second's
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "{}\npane {}x{}\nstart_addr {:#X}\nsolution {}\n",
+            BOARD_HEADER,
+            self.pane_size.width,
+            self.pane_size.height,
+            self.start_addr,
+            self.solution
+        );
+        for (word, offset) in self.words.iter().zip(&self.word_offsets) {
+            text.push_str(&format!("word {} {}\n", offset, word));
+        }
+        for brackets in &self.bracket_sequences {
+            text.push_str(&format!("brackets {} {}\n", brackets.offset, brackets.len));
+        }
+        for row in self.hex_dump.as_bytes().chunks(self.pane_size.width.max(1)) {
+            text.push_str("row ");
+            text.push_str(&String::from_utf8_lossy(row));
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Read a board back from to_text's format and check it can be played
+    pub fn from_text(text: &str) -> Result<Self, BoardErr> {
+        let mut lines = text.lines().map(|line| line.trim());
+        match lines.next() {
+            Some(BOARD_HEADER) => (),
+            // a newer version of the format rather than something that isn't a board at all
+            Some(header) if header.starts_with(BOARD_HEADER_PREFIX) => {
+                return Err(BoardErr::UnsupportedVersion(String::from(
+                    &header[BOARD_HEADER_PREFIX.len()..],
+                )));
+            }
+            _ => return Err(BoardErr::MissingHeader),
+        }
+
+        let mut pane_size = None;
+        let mut start_addr = None;
+        let mut solution = None;
+        let mut words = Vec::new();
+        let mut word_offsets = Vec::new();
+        let mut bracket_sequences = Vec::new();
+        let mut hex_dump = String::new();
+        for (i, line) in lines.enumerate() {
+            // the header was line 1
+            let line_num = i + 2;
+            let invalid_line = || BoardErr::InvalidLine(line_num, String::from(line));
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                [] => (),
+                ["pane", size] => {
+                    let (width, height) = size.split_once('x').ok_or_else(invalid_line)?;
+                    pane_size = Some(PaneSize {
+                        width: width.parse().map_err(|_| invalid_line())?,
+                        height: height.parse().map_err(|_| invalid_line())?,
+                    });
+                }
+                ["start_addr", addr] => {
+                    let hex_digits = addr.trim_start_matches("0x").trim_start_matches("0X");
+                    let addr = usize::from_str_radix(hex_digits, 16).map_err(|_| invalid_line())?;
+                    start_addr = Some(addr);
+                }
+                ["solution", word] => solution = Some(String::from(*word)),
+                ["word", offset, word] => {
+                    word_offsets.push(offset.parse().map_err(|_| invalid_line())?);
+                    words.push(String::from(*word));
+                }
+                ["brackets", offset, len] => bracket_sequences.push(BracketSequence {
+                    offset: offset.parse().map_err(|_| invalid_line())?,
+                    len: len.parse().map_err(|_| invalid_line())?,
+                }),
+                ["row", row] => {
+                    // rows get sliced by byte so keep to single byte chars
+                    let is_row_width = matches!(pane_size, Some(size) if size.width == row.len());
+                    if !is_row_width || !row.is_ascii() {
+                        return Err(invalid_line());
+                    }
+                    hex_dump.push_str(row);
+                }
+                _ => return Err(invalid_line()),
+            }
+        }
+
+        let board = Board {
+            pane_size: pane_size.ok_or(BoardErr::MissingPane)?,
+            hex_dump,
+            words,
+            word_offsets,
+            solution: solution.ok_or(BoardErr::MissingSolution)?,
+            bracket_sequences,
+            start_addr: start_addr.ok_or(BoardErr::MissingStartAddr)?,
+        };
+        board.validate()?;
+        Ok(board)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, BoardErr> {
+        let text =
+            std::fs::read_to_string(path).map_err(|e| BoardErr::ReadFile(path.to_path_buf(), e))?;
+        Self::from_text(&text)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), BoardErr> {
+        std::fs::write(path, self.to_text()).map_err(|e| BoardErr::WriteFile(path.to_path_buf(), e))
+    }
+
+    /// Check a board, e.g. a hand crafted one, holds together well enough to play. Generated boards
+    /// always pass.
+    pub fn validate(&self) -> Result<(), BoardErr> {
+        let PaneSize { width, height } = self.pane_size;
+        let pane_width_in_range = (MIN_PANE_WIDTH..=MAX_PANE_WIDTH).contains(&width);
+        let pane_height_in_range = (MIN_PANE_HEIGHT..=MAX_PANE_HEIGHT).contains(&height);
+        if !pane_width_in_range || !pane_height_in_range {
+            return Err(BoardErr::PaneOutOfRange(width, height));
+        }
+        let dump_size = self.pane_size.bytes() * NUM_PANES;
+        if self.hex_dump.len() != dump_size || !self.hex_dump.is_ascii() {
+            return Err(BoardErr::WrongDumpSize {
+                found: self.hex_dump.len(),
+                expected: dump_size,
+            });
+        }
+        // offsets and lengths come from files so they're added with checked_add, and anything which
+        // overflows is as out of range as it gets
+        if !matches!(self.start_addr.checked_add(dump_size), Some(end_addr) if end_addr <= 0xFFFF) {
+            return Err(BoardErr::StartAddrOutOfRange(self.start_addr));
+        }
+
+        // the password needs at least one decoy
+        if self.words.len() < 2 || self.words.len() != self.word_offsets.len() {
+            return Err(BoardErr::TooFewWords(self.words.len()));
+        }
+        let word_len = self.words[0].len();
+        let mut next_free_offset = 0;
+        for (word, &offset) in self.words.iter().zip(&self.word_offsets) {
+            let is_valid_word = word.len() == word_len
+                && (2..=MAX_WORD_LEN).contains(&word.len())
+                && word.bytes().all(|b| b.is_ascii_alphabetic());
+            if !is_valid_word {
+                return Err(BoardErr::InvalidWord(word.clone()));
+            }
+            // words are listed in the order they appear and can't share chars
+            let word_end = offset.checked_add(word_len);
+            if offset < next_free_offset
+                || word_end.and_then(|end| self.hex_dump.get(offset..end)) != Some(word)
+            {
+                return Err(BoardErr::WordNotAtOffset {
+                    word: word.clone(),
+                    offset,
+                });
+            }
+            next_free_offset = offset + word_len;
+        }
+        if !self.words.contains(&self.solution) {
+            return Err(BoardErr::SolutionNotAWord(self.solution.clone()));
+        }
+
+        // every sequence has to fit in its row before any of their ends can be compared
+        for &BracketSequence { offset, len } in &self.bracket_sequences {
+            let fits_in_dump = matches!(offset.checked_add(len), Some(end) if end <= dump_size);
+            if len < 2 || !fits_in_dump || (offset % width) + len > width {
+                return Err(BoardErr::InvalidBrackets(offset));
+            }
+        }
+        for (i, brackets) in self.bracket_sequences.iter().enumerate() {
+            let BracketSequence { offset, len } = *brackets;
+            let bytes = self.hex_dump.as_bytes();
+            let (open, close) = (bytes[offset] as char, bytes[offset + len - 1] as char);
+            let is_bracket_pair = BRACKET_PAIRS.contains(&(open, close));
+            let overlaps_word = self
+                .word_offsets
+                .iter()
+                .any(|&word_offset| word_offset < offset + len && offset < word_offset + word_len);
+            let overlaps_brackets = self.bracket_sequences.iter().enumerate().any(|(j, other)| {
+                j != i && other.offset < offset + len && offset < other.offset + other.len
+            });
+            if !is_bracket_pair || overlaps_word || overlaps_brackets {
+                return Err(BoardErr::InvalidBrackets(offset));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile::ProfileErr;
+    use crate::utils::seeded_rng::SeededRangeRng;
+    use snm_rand_utils::mocks as rand_mocks;
+    use snm_rand_utils::range_rng::ThreadRangeRng;
+
+    #[test]
+    fn test_word_generation() {
+        // use a single-value rng for value 0. This will make sure the goal_word is the first word in the original word list
+        let mut rng = rand_mocks::SingleValueRangeRng::new(0);
+
+        let test_hd_distribution = [
+            HDDEntry {
+                num_words: 1,
+                hamming_distance: 1,
+            },
+            HDDEntry {
+                num_words: 2,
+                hamming_distance: 2,
+            },
+            HDDEntry {
+                num_words: 3,
+                hamming_distance: 3,
+            },
+            HDDEntry {
+                num_words: 4,
+                hamming_distance: 4,
+            },
+        ];
+
+        let goal_word = "dude";
+        let words = [
+            goal_word, // 0
+            "dede",    // 1
+            "door",    // 3
+            "dodo",    // 2
+            "doom",    // 3
+            "abba",    // 4
+            "rude",    // 1
+            "duds",    // 1
+            "rube",    // 2
+            "cube",    // 2
+            "sick",    // 4
+            "stop",    // 4
+            "soil",    // 4
+            "roll",    // 4
+        ];
+
+        let expected_generated_words = [
+            goal_word, // goal
+            "dede",    // hd 1
+            "dodo", "rube", // hd 2
+            "door", "doom", "abba", // hd 3
+            "sick", "stop", "soil", "roll", // hd 4
+        ];
+
+        let test_dict = EnglishDictChunk::new_mock(4, &words);
+        let (generated_words, solution) =
+            generate_words(&test_dict, &test_hd_distribution, 11, &mut rng);
+
+        assert_eq!(solution, goal_word);
+        assert_eq!(generated_words, expected_generated_words);
+
+        // a smaller word count stops part way through the buckets
+        let (generated_words, _) = generate_words(&test_dict, &test_hd_distribution, 6, &mut rng);
+        assert_eq!(generated_words, expected_generated_words[..6]);
+
+        // and a bigger one keeps taking words from the last bucket
+        let (generated_words, _) =
+            generate_words(&test_dict, &test_hd_distribution[..1], 4, &mut rng);
+        assert_eq!(generated_words, [goal_word, "dede", "rude", "duds"]);
+    }
+
+    #[test]
+    fn test_obfuscate_words() {
+        let mut rng = ThreadRangeRng::new();
+        let words: Vec<String> = ["apple", "orange", "banana"]
+            .iter()
+            .map(|s| String::from(*s))
+            .collect();
+
+        const HEX_BYTE_COUNT: usize = 100;
+        let (obfuscated_words, offsets) = obfuscate_words(&words, HEX_BYTE_COUNT, &mut rng);
+
+        assert_eq!(HEX_BYTE_COUNT, obfuscated_words.len());
+        for (word, word_offset) in words.iter().zip(offsets.iter()) {
+            let word_in_blob = &obfuscated_words[*word_offset..][..word.len()];
+            assert_eq!(word, word_in_blob);
+        }
+    }
+
+    #[test]
+    fn test_insert_bracket_sequences() {
+        let words: Vec<String> = ["apple", "orange", "banana"]
+            .iter()
+            .map(|s| String::from(*s))
+            .collect();
+
+        const ROW_WIDTH: usize = 12;
+        const HEX_BYTE_COUNT: usize = ROW_WIDTH * 16;
+        // enough seeds that some try to place a sequence inside another
+        for seed in 0..50 {
+            let mut rng = SeededRangeRng::new(seed);
+            let (mut hex_dump, offsets) = obfuscate_words(&words, HEX_BYTE_COUNT, &mut rng);
+            let bracket_sequences = insert_bracket_sequences(&mut hex_dump, ROW_WIDTH, 8, &mut rng);
+
+            assert!(!bracket_sequences.is_empty());
+            assert!(bracket_sequences.len() <= 8);
+            assert_eq!(HEX_BYTE_COUNT, hex_dump.len());
+            for (word, word_offset) in words.iter().zip(offsets.iter()) {
+                assert_eq!(word, &hex_dump[*word_offset..][..word.len()]);
+            }
+
+            for b in &bracket_sequences {
+                let brackets = &hex_dump[b.offset..][..b.len];
+                let open = brackets.chars().next().unwrap();
+                let close = brackets.chars().last().unwrap();
+                assert!(BRACKET_PAIRS.contains(&(open, close)), "{}", brackets);
+                assert_eq!(b.offset / ROW_WIDTH, (b.offset + b.len - 1) / ROW_WIDTH);
+                assert!(
+                    brackets[1..b.len - 1]
+                        .bytes()
+                        .all(|c| GARBAGE_CHARS.contains(&c)),
+                    "seed {}: {}",
+                    seed,
+                    brackets
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_difficulty_display_round_trips() {
+        for d in &ALL_DIFFICULTIES {
+            assert_eq!(d.to_string().parse::<Difficulty>(), Ok(*d));
+        }
+    }
+
+    #[test]
+    fn test_seeded_board_generation_is_reproducible() {
+        let dict_words = [
+            "dude", "dede", "door", "dodo", "doom", "abba", "rude", "duds", "rube", "cube", "sick",
+            "stop", "soil", "roll", "pens", "pans", "pils", "dull", "pins", "pent", "miss",
+        ];
+        let test_dict = EnglishDictChunk::new_mock(4, &dict_words);
+        let profile = DifficultyProfile::preset(Difficulty::VeryEasy);
+
+        let generate_board = |seed: u64| {
+            let mut rng = SeededRangeRng::new(seed);
+            Board::generate(&profile, &test_dict, &mut rng).unwrap()
+        };
+
+        assert_eq!(generate_board(42), generate_board(42));
+        assert_ne!(generate_board(42), generate_board(43));
+
+        let board = generate_board(42);
+        assert_eq!(board.hex_dump.len(), 12 * 16 * 2);
+        assert_eq!(board.words.len(), profile.word_count);
+        assert!(board.words.contains(&board.solution));
+        assert!(board.start_addr + board.hex_dump.len() <= 0xFFFF);
+
+        // a dictionary without enough words can't fill a board
+        let small_dict = EnglishDictChunk::new_mock(4, &dict_words[..5]);
+        assert!(matches!(
+            Board::generate(&profile, &small_dict, &mut SeededRangeRng::new(42)),
+            Err(GenerateErr::LoadDict(DictLoadErr::NotEnoughWords {
+                required: 12,
+                ..
+            }))
+        ));
+
+        // only the words far enough from the password count as found
+        let far_profile = DifficultyProfile {
+            distance_buckets: vec![HDDEntry {
+                num_words: 11,
+                hamming_distance: 4,
+            }],
+            ..profile.clone()
+        };
+        let split_dict = EnglishDictChunk::new_mock(4, &["aaaa", "aaab", "bbbb", "bbba"]);
+        assert!(matches!(
+            Board::generate(&far_profile, &split_dict, &mut SeededRangeRng::new(42)),
+            Err(GenerateErr::LoadDict(DictLoadErr::NotEnoughWords {
+                word_len: 4,
+                found: 2,
+                required: 12,
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_solvability_limits() {
+        let test_dict = EnglishDictChunk::new_mock(
+            4,
+            &[
+                "dude", "dede", "door", "dodo", "doom", "abba", "rude", "duds", "rube", "cube",
+                "sick", "stop", "soil", "roll", "pens", "pans", "pils", "dull", "pins", "pent",
+            ],
+        );
+        let mut profile = DifficultyProfile::preset(Difficulty::VeryEasy);
+        profile.word_count = 5;
+
+        let worst_case = Board::generate(&profile, &test_dict, &mut SeededRangeRng::new(7))
+            .unwrap()
+            .worst_case_attempts();
+        assert!((2..=5).contains(&worst_case));
+
+        // every board has to meet the limits, however many tries it takes
+        profile.min_solvability = Some(worst_case);
+        profile.max_solvability = Some(worst_case);
+        for seed in 0..10 {
+            let board = Board::generate(&profile, &test_dict, &mut SeededRangeRng::new(seed));
+            assert_eq!(board.unwrap().worst_case_attempts(), worst_case);
+        }
+
+        // five words can't all be told apart by one guess, which the profile doesn't allow
+        profile.min_solvability = Some(1);
+        profile.max_solvability = None;
+        assert!(matches!(
+            profile.validate(),
+            Err(ProfileErr::MinSolvabilityOfOne(5))
+        ));
+
+        // and none of these boards leave the best guesser needing a guess for every word
+        profile.min_solvability = None;
+        profile.max_solvability = Some(5);
+        assert!(matches!(
+            Board::generate(&profile, &test_dict, &mut SeededRangeRng::new(7)),
+            Err(GenerateErr::SolvabilityNotMet { tries: 100 })
+        ));
+    }
+
+    #[test]
+    fn test_board_text_round_trips() {
+        let test_dict = EnglishDictChunk::new_mock(4, &["dude", "dede", "door", "dodo", "doom"]);
+        let mut profile = DifficultyProfile::preset(Difficulty::VeryEasy);
+        profile.word_count = 5;
+        let board = Board::generate(&profile, &test_dict, &mut SeededRangeRng::new(7)).unwrap();
+        assert!(board.validate().is_ok());
+
+        let text = board.to_text();
+        assert!(text.starts_with("fonv_cracker board v1\npane 12x16\nstart_addr 0x"));
+        assert_eq!(
+            text.lines().filter(|line| line.starts_with("row ")).count(),
+            32
+        );
+        assert_eq!(Board::from_text(&text).unwrap(), board);
+    }
+
+    #[test]
+    fn test_invalid_board_text() {
+        const BOARD_TEXT: &str = "fonv_cracker board v1\npane 4x4\nstart_addr 0xCC10\n\
+            solution grape\nword 1 apple\nword 16 grape\nbrackets 8 4\n\
+            row #app\nrow le$%\nrow <#$>\nrow %%%%\nrow grap\nrow e%%%\nrow %%%%\nrow %%%%\n";
+        let board = Board::from_text(BOARD_TEXT).unwrap();
+        assert_eq!(board.words, ["apple", "grape"]);
+        assert_eq!(board.hex_dump, "#apple$%<#$>%%%%grape%%%%%%%%%%%");
+        assert_eq!(board.start_addr, 0xCC10);
+
+        let modified = |from: &str, to: &str| Board::from_text(&BOARD_TEXT.replacen(from, to, 1));
+        assert!(matches!(
+            modified("board v1", "board v2"),
+            Err(BoardErr::UnsupportedVersion(version)) if version == "v2"
+        ));
+        assert!(matches!(
+            modified("fonv_cracker board v1\n", ""),
+            Err(BoardErr::MissingHeader)
+        ));
+        assert!(matches!(
+            modified("solution grape\n", ""),
+            Err(BoardErr::MissingSolution)
+        ));
+        assert!(matches!(
+            modified("row le$%", "row le$%%"),
+            Err(BoardErr::InvalidLine(9, _))
+        ));
+        assert!(matches!(
+            modified("row %%%%\n", ""),
+            Err(BoardErr::WrongDumpSize {
+                found: 28,
+                expected: 32
+            })
+        ));
+        assert!(matches!(
+            modified("0xCC10", "0xFFF0"),
+            Err(BoardErr::StartAddrOutOfRange(0xFFF0))
+        ));
+        assert!(matches!(
+            modified("word 16", "word 15"),
+            Err(BoardErr::WordNotAtOffset { offset: 15, .. })
+        ));
+        assert!(matches!(
+            modified("solution grape", "solution pear"),
+            Err(BoardErr::SolutionNotAWord(_))
+        ));
+        assert!(matches!(
+            modified("row <#$>", "row <#$)"),
+            Err(BoardErr::InvalidBrackets(8))
+        ));
+        assert!(matches!(
+            modified("brackets 8 4", "brackets 2 4"),
+            Err(BoardErr::InvalidBrackets(2))
+        ));
+        assert!(matches!(
+            modified("brackets 8 4", "brackets 8 4\nbrackets 9 2"),
+            Err(BoardErr::InvalidBrackets(8))
+        ));
+        assert!(matches!(
+            modified("brackets 8 4", "brackets 8 4\nbrackets 8 4"),
+            Err(BoardErr::InvalidBrackets(8))
+        ));
+
+        // offsets big enough to overflow are just out of range
+        assert!(matches!(
+            modified("word 16 grape", &format!("word {} grape", usize::MAX)),
+            Err(BoardErr::WordNotAtOffset {
+                offset: usize::MAX,
+                ..
+            })
+        ));
+        assert!(matches!(
+            modified("brackets 8 4", &format!("brackets {} 2", usize::MAX)),
+            Err(BoardErr::InvalidBrackets(usize::MAX))
+        ));
+        assert!(matches!(
+            modified("brackets 8 4", &format!("brackets 8 {}", usize::MAX)),
+            Err(BoardErr::InvalidBrackets(8))
+        ));
+        assert!(matches!(
+            modified("0xCC10", &format!("{:#X}", usize::MAX)),
+            Err(BoardErr::StartAddrOutOfRange(usize::MAX))
+        ));
+    }
+}
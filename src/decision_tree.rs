@@ -0,0 +1,289 @@
+use crate::board::MAX_ATTEMPTS;
+use crate::solver::{filter_matching_passwords, score_guess, KnownGuess};
+use crate::utils::str_utils::matching_char_count_ignore_case;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+/// A guessing strategy for a password list. Each node is the word to guess next and the subtree to
+/// follow for each likeness count the game could report back. A full match ends the game so it
+/// never has a subtree.
+#[derive(Debug, PartialEq, Eq)]
+pub struct DecisionTree {
+    guess: String,
+    responses: Vec<(usize, DecisionTree)>,
+}
+
+impl DecisionTree {
+    pub fn worst_case_attempts(&self) -> usize {
+        1 + self
+            .responses
+            .iter()
+            .map(|(_, subtree)| subtree.worst_case_attempts())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn write_text(&self, depth: usize, out: &mut String) {
+        out.push_str(&format!("guess {}\n", self.guess));
+        for (likeness, subtree) in &self.responses {
+            let indent = "  ".repeat(depth + 1);
+            out.push_str(&format!("{}{} correct -> ", indent, likeness));
+            subtree.write_text(depth + 1, out);
+        }
+    }
+
+    fn write_json(&self, depth: usize, out: &mut String) {
+        let indent = "  ".repeat(depth);
+        out.push_str("{\n");
+        out.push_str(&format!(
+            "{}  \"guess\": \"{}\",\n",
+            indent,
+            escape_json_string(&self.guess)
+        ));
+        out.push_str(&format!("{}  \"responses\": [", indent));
+        for (i, (likeness, subtree)) in self.responses.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str(&format!("\n{}    {{\n", indent));
+            out.push_str(&format!("{}      \"likeness\": {},\n", indent, likeness));
+            out.push_str(&format!("{}      \"next\": ", indent));
+            subtree.write_json(depth + 3, out);
+            out.push_str(&format!("\n{}    }}", indent));
+        }
+        if !self.responses.is_empty() {
+            out.push_str(&format!("\n{}  ", indent));
+        }
+        out.push_str("]\n");
+        out.push_str(&format!("{}}}", indent));
+    }
+}
+
+fn escape_json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Remembers the deepest search which failed to solve a set of candidates so the same subproblem
+// isn't searched again when another branch of the tree leads back to it.
+type FailedSearchCache<'a> = HashMap<Vec<&'a str>, usize>;
+
+fn try_build_tree<'a>(
+    candidates: &[&'a str],
+    guesses: &[&'a str],
+    max_attempts: usize,
+    failed_searches: &mut FailedSearchCache<'a>,
+) -> Option<DecisionTree> {
+    if max_attempts == 0 || candidates.is_empty() {
+        return None;
+    }
+
+    if candidates.len() == 1 {
+        return Some(DecisionTree {
+            guess: String::from(candidates[0]),
+            responses: Vec::new(),
+        });
+    }
+
+    if max_attempts == 1 {
+        return None;
+    }
+
+    if let Some(failed_attempts) = failed_searches.get(candidates) {
+        if max_attempts <= *failed_attempts {
+            return None;
+        }
+    }
+
+    // Try the most promising guesses first. Any word in the list can be guessed, but when guesses
+    // score the same prefer one that could still be the password.
+    let mut ordered_guesses: Vec<(&str, _, bool)> = guesses
+        .iter()
+        .map(|guess| {
+            let score = score_guess(guess, candidates);
+            let is_candidate = candidates.contains(guess);
+            (*guess, score, is_candidate)
+        })
+        .collect();
+    ordered_guesses.sort_by_key(|(_, score, is_candidate)| (*score, !*is_candidate));
+
+    'guesses: for (guess, _, _) in ordered_guesses {
+        let mut likenesses: Vec<usize> = candidates
+            .iter()
+            .map(|candidate| matching_char_count_ignore_case(candidate, guess))
+            .filter(|likeness| *likeness != guess.len())
+            .collect();
+        likenesses.sort_unstable();
+        likenesses.dedup();
+
+        let mut responses = Vec::with_capacity(likenesses.len());
+        for likeness in likenesses {
+            let known_guess = KnownGuess::new(guess, likeness);
+            let mut partition = filter_matching_passwords(&known_guess, candidates.to_vec());
+
+            // a guess which can't rule out any candidates gets us no closer to the password
+            if partition.len() == candidates.len() {
+                continue 'guesses;
+            }
+
+            partition.sort_unstable();
+            match try_build_tree(&partition, guesses, max_attempts - 1, failed_searches) {
+                Some(subtree) => responses.push((likeness, subtree)),
+                None => continue 'guesses,
+            }
+        }
+
+        return Some(DecisionTree {
+            guess: String::from(guess),
+            responses,
+        });
+    }
+
+    failed_searches.insert(candidates.to_vec(), max_attempts);
+    None
+}
+
+/// Build the decision tree which needs the fewest attempts in the worst case to find the password.
+pub fn build_decision_tree<S>(passwords: &[S]) -> Option<DecisionTree>
+where
+    S: AsRef<str>,
+{
+    let mut candidates: Vec<&str> = passwords.iter().map(|p| p.as_ref()).collect();
+    candidates.sort_unstable();
+    candidates.dedup();
+
+    // Guessing each candidate one at a time is always enough, so the search never needs to look
+    // deeper than the number of candidates.
+    let mut failed_searches = FailedSearchCache::new();
+    (1..=candidates.len()).find_map(|max_attempts| {
+        try_build_tree(&candidates, &candidates, max_attempts, &mut failed_searches)
+    })
+}
+
+pub fn format_decision_tree(tree: &DecisionTree, format: OutputFormat) -> String {
+    let worst_case_attempts = tree.worst_case_attempts();
+    let solvable = worst_case_attempts <= MAX_ATTEMPTS;
+
+    let mut out = String::new();
+    match format {
+        OutputFormat::Text => {
+            tree.write_text(0, &mut out);
+            out.push_str(&format!(
+                "\nWorst case: {} attempt(s). {} within {} attempts.\n",
+                worst_case_attempts,
+                if solvable {
+                    "Guaranteed solvable"
+                } else {
+                    "NOT guaranteed solvable"
+                },
+                MAX_ATTEMPTS,
+            ));
+        }
+        OutputFormat::Json => {
+            out.push_str("{\n");
+            out.push_str(&format!("  \"max_attempts\": {},\n", MAX_ATTEMPTS));
+            out.push_str(&format!(
+                "  \"worst_case_attempts\": {},\n",
+                worst_case_attempts
+            ));
+            out.push_str(&format!("  \"solvable\": {},\n", solvable));
+            out.push_str("  \"tree\": ");
+            tree.write_json(1, &mut out);
+            out.push_str("\n}\n");
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(guess: &str) -> DecisionTree {
+        DecisionTree {
+            guess: String::from(guess),
+            responses: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn check_build_decision_tree() {
+        let tree = build_decision_tree(&["xyz", "abd", "abc"]).unwrap();
+
+        // abc splits the other words apart so either response leaves a single password
+        assert_eq!(
+            tree,
+            DecisionTree {
+                guess: String::from("abc"),
+                responses: vec![(0, leaf("xyz")), (2, leaf("abd"))],
+            }
+        );
+        assert_eq!(tree.worst_case_attempts(), 2);
+    }
+
+    #[test]
+    fn check_unsolvable_decision_tree() {
+        // no word shares a letter with any other so every guess only rules out itself
+        let passwords = ["aaa", "bbb", "ccc", "ddd", "eee", "fff"];
+        let tree = build_decision_tree(&passwords).unwrap();
+        assert_eq!(tree.worst_case_attempts(), passwords.len());
+        assert!(tree.worst_case_attempts() > MAX_ATTEMPTS);
+    }
+
+    #[test]
+    fn check_format_decision_tree() {
+        let tree = DecisionTree {
+            guess: String::from("abc"),
+            responses: vec![(0, leaf("xyz")), (2, leaf("abd"))],
+        };
+
+        assert_eq!(
+            format_decision_tree(&tree, OutputFormat::Text),
+            "guess abc\n  0 correct -> guess xyz\n  2 correct -> guess abd\n\n\
+             Worst case: 2 attempt(s). Guaranteed solvable within 4 attempts.\n",
+        );
+
+        let expected_json = r#"{
+  "max_attempts": 4,
+  "worst_case_attempts": 2,
+  "solvable": true,
+  "tree": {
+    "guess": "abc",
+    "responses": [
+      {
+        "likeness": 0,
+        "next": {
+          "guess": "xyz",
+          "responses": []
+        }
+      },
+      {
+        "likeness": 2,
+        "next": {
+          "guess": "abd",
+          "responses": []
+        }
+      }
+    ]
+  }
+}
+"#;
+        assert_eq!(
+            format_decision_tree(&tree, OutputFormat::Json),
+            expected_json
+        );
+    }
+}
@@ -1,16 +1,126 @@
 use crate::utils::str_utils::hamming_dist_ignore_case;
 use snm_rand_utils::range_rng::{select_rand, RangeRng};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
-// Each dict chunk represents all words of the same length from our src dict. This partitioning is a
-// quick optimization since the cracker game will only concern itself with words of the same length.
+/// Environment variables used to pick the dictionary when it isn't given on the cmdline. The mixed
+/// length file takes priority if both are set.
+pub const DICT_DIR_ENV_VAR: &str = "FONV_CRACKER_DICT_DIR";
+pub const DICT_FILE_ENV_VAR: &str = "FONV_CRACKER_DICT_FILE";
+
+#[cfg(not(feature = "embedded-dict"))]
+const DEFAULT_DICT_DIR: &str = "assets/dict";
+
+/// Where the word lists come from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DictSource {
+    // The word lists compiled into the binary, or the repo's assets directory when they aren't
+    Builtin,
+    // A directory of N_char_words_alpha.txt files, one per word length
+    Dir(PathBuf),
+    // A single file of words of any length which gets split by length on load
+    MixedLengthFile(PathBuf),
+}
+
+impl DictSource {
+    pub fn from_env() -> Self {
+        if let Some(dict_file) = std::env::var_os(DICT_FILE_ENV_VAR) {
+            DictSource::MixedLengthFile(PathBuf::from(dict_file))
+        } else if let Some(dict_dir) = std::env::var_os(DICT_DIR_ENV_VAR) {
+            DictSource::Dir(PathBuf::from(dict_dir))
+        } else {
+            DictSource::Builtin
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum DictLoadErr {
+    ReadFailed(PathBuf, std::io::Error),
+    NoWordsOfLength(usize),
+    NotEnoughWords {
+        word_len: usize,
+        found: usize,
+        required: usize,
+    },
+}
+
+impl std::fmt::Display for DictLoadErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DictLoadErr::ReadFailed(path, err) => {
+                write!(
+                    f,
+                    "couldn't read dictionary file {}: {}",
+                    path.display(),
+                    err
+                )
+            }
+            DictLoadErr::NoWordsOfLength(word_len) => {
+                write!(f, "the dictionary has no {} letter words", word_len)
+            }
+            DictLoadErr::NotEnoughWords {
+                word_len,
+                found,
+                required,
+            } => write!(
+                f,
+                "the dictionary only has {} usable {} letter words but {} are needed",
+                found, word_len, required
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "embedded-dict")]
+macro_rules! embedded_dict_files {
+    ($($word_len:literal),*) => {
+        fn embedded_dict_file(word_len: usize) -> Option<&'static str> {
+            match word_len {
+                $($word_len => Some(include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/assets/dict/",
+                    stringify!($word_len),
+                    "_char_words_alpha.txt"
+                ))),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+#[cfg(feature = "embedded-dict")]
+embedded_dict_files!(
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26,
+    27, 28, 29, 30, 31
+);
+
+fn dict_file_name(word_len: usize) -> String {
+    format!("{}_char_words_alpha.txt", word_len)
+}
+
+fn read_dict_file(path: &Path) -> Result<String, DictLoadErr> {
+    std::fs::read_to_string(path).map_err(|e| DictLoadErr::ReadFailed(path.to_path_buf(), e))
+}
+
+/// Each dict chunk represents all words of the same length from our src dict. This partitioning is a
+/// quick optimization since the cracker game will only concern itself with words of the same length.
 pub struct EnglishDictChunk {
     word_len: usize,
     word_set: Vec<String>,
+    // Indices into word_set, sorted by word, so lookups can binary search. The index is kept separate
+    // so word_set stays in its original order, which the hamming distance iteration order follows.
+    sorted_word_indices: Vec<usize>,
 }
 
+/// Every other word in a dict chunk in order of its hamming distance from a word, closest first.
+/// Yields each word along with its distance.
 pub struct HammingDistanceIterator<'a> {
-    cmp_word: String,
     dict_chunk: &'a EnglishDictChunk,
+    // Indices into the dict chunk's word_set bucketed by their hamming distance from the compared
+    // word. Each bucket keeps the word_set order. Bucket 0 only holds the compared word itself so
+    // iteration starts from bucket 1.
+    distance_buckets: Vec<Vec<usize>>,
     next_candidate_distance: usize,
     next_item_candidate_index: usize,
 }
@@ -19,57 +129,128 @@ impl EnglishDictChunk {
     #[cfg(test)]
     pub fn new_mock(word_len: usize, word_set: &[&str]) -> Self {
         assert!(word_set.iter().all(|w| w.len() == word_len));
+        Self::new(
+            word_len,
+            word_set.iter().map(|s| String::from(*s)).collect(),
+        )
+    }
+
+    fn new(word_len: usize, word_set: Vec<String>) -> Self {
+        let mut sorted_word_indices: Vec<usize> = (0..word_set.len()).collect();
+        sorted_word_indices.sort_unstable_by(|a, b| word_set[*a].cmp(&word_set[*b]));
         EnglishDictChunk {
             word_len,
-            word_set: word_set.iter().map(|s| String::from(*s)).collect(),
+            word_set,
+            sorted_word_indices,
+        }
+    }
+
+    /// Load the words of one length. Fails if there aren't any.
+    pub fn load(source: &DictSource, word_len: usize) -> Result<Self, DictLoadErr> {
+        let chunk = match source {
+            #[cfg(feature = "embedded-dict")]
+            DictSource::Builtin => {
+                Self::from_word_list(word_len, embedded_dict_file(word_len).unwrap_or(""))
+            }
+            #[cfg(not(feature = "embedded-dict"))]
+            DictSource::Builtin => Self::load_from_dir(Path::new(DEFAULT_DICT_DIR), word_len)?,
+            DictSource::Dir(dict_dir) => Self::load_from_dir(dict_dir, word_len)?,
+            DictSource::MixedLengthFile(dict_file_path) => {
+                Self::from_word_list(word_len, &read_dict_file(dict_file_path)?)
+            }
+        };
+
+        // an empty chunk has no words to generate or check against so treat it as missing
+        if chunk.word_set.is_empty() {
+            return Err(DictLoadErr::NoWordsOfLength(word_len));
         }
+        Ok(chunk)
     }
 
-    pub fn load(word_len: usize) -> Self {
-        let dict_file_name = format!("assets/dict/{}_char_words_alpha.txt", word_len);
-        let word_set = snm_simple_file::read_lines(&dict_file_name).collect();
-        EnglishDictChunk { word_len, word_set }
+    fn load_from_dir(dict_dir: &Path, word_len: usize) -> Result<Self, DictLoadErr> {
+        let dict_file = read_dict_file(&dict_dir.join(dict_file_name(word_len)))?;
+        Ok(Self::from_word_list(word_len, &dict_file))
     }
 
+    /// Pick out the words of the given length from a word list with one word per line. Anything that
+    /// isn't a plain word is skipped since the game and solver only deal in letters.
+    pub fn from_word_list(word_len: usize, word_list: &str) -> Self {
+        let mut seen_words = HashSet::new();
+        let word_set = word_list
+            .lines()
+            .map(|line| line.trim())
+            .filter(|word| word.len() == word_len && word.chars().all(|c| c.is_ascii_alphabetic()))
+            .map(|word| word.to_ascii_lowercase())
+            .filter(|word| seen_words.insert(word.clone()))
+            .collect();
+        Self::new(word_len, word_set)
+    }
+
+    pub fn word_len(&self) -> usize {
+        self.word_len
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.word_set.len()
+    }
+
+    /// The word has to be lowercase and the chunk's length
     pub fn is_word(&self, word: &str) -> bool {
         assert_eq!(self.word_len, word.len());
-        self.word_set.iter().any(|word_in_set| word_in_set == word)
+        self.sorted_word_indices
+            .binary_search_by(|i| self.word_set[*i].as_str().cmp(word))
+            .is_ok()
     }
 
     pub fn get_random_word(&self, rng: &mut dyn RangeRng<usize>) -> String {
         select_rand(&self.word_set, rng).clone()
     }
 
-    pub fn get_hamming_distance_sorted_words(&self, word: &str) -> HammingDistanceIterator {
+    pub fn get_hamming_distance_sorted_words(&self, word: &str) -> HammingDistanceIterator<'_> {
+        // compute every word's distance once up front rather than rescanning for each distance
+        let mut distance_buckets = vec![Vec::new(); self.word_len + 1];
+        for (i, candidate) in self.word_set.iter().enumerate() {
+            distance_buckets[hamming_dist_ignore_case(candidate, word)].push(i);
+        }
+
         HammingDistanceIterator {
-            cmp_word: String::from(word),
             dict_chunk: self,
+            distance_buckets,
             next_candidate_distance: 1,
             next_item_candidate_index: 0,
         }
     }
 }
 
+impl<'a> HammingDistanceIterator<'a> {
+    /// Skip past any remaining words closer than min_distance
+    pub fn skip_to_distance(&mut self, min_distance: usize) {
+        if self.next_candidate_distance < min_distance {
+            self.next_candidate_distance = min_distance;
+            self.next_item_candidate_index = 0;
+        }
+    }
+}
+
 impl<'a> Iterator for HammingDistanceIterator<'a> {
     type Item = (&'a str, usize);
 
     fn next(&mut self) -> Option<Self::Item> {
         while self.next_candidate_distance <= self.dict_chunk.word_len {
-            let candidate_index = self.next_item_candidate_index;
-            let current_candidate_distance = self.next_candidate_distance;
+            let bucket = &self.distance_buckets[self.next_candidate_distance];
 
-            // if we've made it to the end of the list, start over at the beginning and look for the next hamming distance
-            self.next_item_candidate_index += 1;
-            if self.next_item_candidate_index >= self.dict_chunk.word_set.len() {
+            // if we've made it to the end of this distance's bucket, move on to the next distance
+            if self.next_item_candidate_index >= bucket.len() {
                 self.next_item_candidate_index = 0;
                 self.next_candidate_distance += 1;
+                continue;
             }
 
+            let candidate_index = bucket[self.next_item_candidate_index];
+            self.next_item_candidate_index += 1;
+
             let candidate = &self.dict_chunk.word_set[candidate_index];
-            let candidate_hamming_distance = hamming_dist_ignore_case(&candidate, &self.cmp_word);
-            if candidate_hamming_distance == current_candidate_distance {
-                return Some((candidate, candidate_hamming_distance));
-            }
+            return Some((candidate, self.next_candidate_distance));
         }
 
         None
@@ -105,12 +286,154 @@ mod tests {
         );
 
         let dict_chunk = EnglishDictChunk::new_mock(4, &word_set);
-        let words_sorted_by_haming_distance: Vec<(&str, usize)> = dict_chunk
-            .get_hamming_distance_sorted_words(&word)
-            .collect();
+        let words_sorted_by_haming_distance: Vec<(&str, usize)> =
+            dict_chunk.get_hamming_distance_sorted_words(word).collect();
         assert_eq!(
             words_sorted_by_haming_distance,
             expected_words_sorted_by_hamming_distance
         );
     }
+
+    #[test]
+    fn test_hamming_distance_iterator_skip_to_distance() {
+        let word_set = [
+            //   3,      1,      2,      4,      0,      1,      1,      3
+            "adds", "pans", "pils", "dull", "pens", "pins", "pent", "miss",
+        ];
+        let dict_chunk = EnglishDictChunk::new_mock(4, &word_set);
+
+        // take the 3 nearest words at a distance of at least 2
+        let mut hamming_distance_sorted_iter = dict_chunk.get_hamming_distance_sorted_words("pens");
+        hamming_distance_sorted_iter.skip_to_distance(2);
+        let nearest_words: Vec<(&str, usize)> = hamming_distance_sorted_iter.take(3).collect();
+        assert_eq!(nearest_words, [("pils", 2), ("adds", 3), ("miss", 3)]);
+
+        // skipping to a closer distance than the iterator has reached doesn't go backwards
+        let mut hamming_distance_sorted_iter = dict_chunk.get_hamming_distance_sorted_words("pens");
+        hamming_distance_sorted_iter.skip_to_distance(3);
+        assert_eq!(hamming_distance_sorted_iter.next(), Some(("adds", 3)));
+        hamming_distance_sorted_iter.skip_to_distance(1);
+        assert_eq!(hamming_distance_sorted_iter.next(), Some(("miss", 3)));
+        hamming_distance_sorted_iter.skip_to_distance(4);
+        assert_eq!(hamming_distance_sorted_iter.next(), Some(("dull", 4)));
+        assert_eq!(hamming_distance_sorted_iter.next(), None);
+    }
+
+    #[test]
+    fn test_is_word() {
+        let dict_chunk = EnglishDictChunk::new_mock(4, &["pens", "adds", "pans", "dull"]);
+        assert!(dict_chunk.is_word("pens"));
+        assert!(dict_chunk.is_word("adds"));
+        assert!(dict_chunk.is_word("dull"));
+        assert!(!dict_chunk.is_word("pins"));
+        assert!(!dict_chunk.is_word("aaaa"));
+        assert!(!dict_chunk.is_word("zzzz"));
+    }
+
+    #[test]
+    fn test_split_mixed_length_word_list() {
+        let word_list = "pens\r\nadds\nfallout\n  Vault \nnuka-cola\nPENS\n\npip\nboy\nmegaton\n";
+
+        let four_letter_chunk = EnglishDictChunk::from_word_list(4, word_list);
+        assert_eq!(four_letter_chunk.word_set, ["pens", "adds"]);
+        let five_letter_chunk = EnglishDictChunk::from_word_list(5, word_list);
+        assert_eq!(five_letter_chunk.word_set, ["vault"]);
+        let seven_letter_chunk = EnglishDictChunk::from_word_list(7, word_list);
+        assert_eq!(seven_letter_chunk.word_set, ["fallout", "megaton"]);
+        assert!(seven_letter_chunk.is_word("megaton"));
+        // words with anything but letters are skipped
+        assert_eq!(
+            EnglishDictChunk::from_word_list(9, word_list).word_count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let missing_dir = DictSource::Dir(PathBuf::from("no/such/dict/dir"));
+        match EnglishDictChunk::load(&missing_dir, 4) {
+            Err(DictLoadErr::ReadFailed(path, _)) => {
+                assert_eq!(path, Path::new("no/such/dict/dir/4_char_words_alpha.txt"))
+            }
+            _ => panic!("expected a read failure"),
+        }
+
+        // the builtin dictionary doesn't go past 31 letter words
+        match EnglishDictChunk::load(&DictSource::Builtin, 40) {
+            Err(DictLoadErr::ReadFailed(..)) | Err(DictLoadErr::NoWordsOfLength(40)) => (),
+            _ => panic!("expected no words to be found"),
+        }
+    }
+
+    #[cfg(feature = "embedded-dict")]
+    #[test]
+    fn test_embedded_dict_matches_assets() {
+        for word_len in 1..=31 {
+            let embedded_chunk =
+                EnglishDictChunk::from_word_list(word_len, embedded_dict_file(word_len).unwrap());
+            let assets_chunk =
+                EnglishDictChunk::load_from_dir(Path::new("assets/dict"), word_len).unwrap();
+            assert_eq!(embedded_chunk.word_set, assets_chunk.word_set);
+        }
+        assert!(embedded_dict_file(32).is_none());
+    }
+
+    // Not a real test. Run with `cargo test --release -- --ignored --nocapture` to time loading
+    // each dictionary chunk and looking up every word in it (plus as many misses).
+    #[test]
+    #[ignore]
+    fn bench_load_and_lookup_every_dict_chunk() {
+        println!(
+            "{:>8} {:>8} {:>12} {:>12} {:>14}",
+            "word_len", "words", "load (ms)", "lookup (ms)", "ns per lookup"
+        );
+
+        // a chunk which fails to load is reported and the rest are still timed
+        let mut failures = Vec::new();
+        for word_len in 1..=31 {
+            let load_start = std::time::Instant::now();
+            let dict_chunk = match EnglishDictChunk::load(&DictSource::Builtin, word_len) {
+                Ok(dict_chunk) => dict_chunk,
+                // some word lists are empty, e.g. there are no 26 letter words
+                Err(DictLoadErr::NoWordsOfLength(_)) => {
+                    println!("{:>8} {:>8}", word_len, 0);
+                    continue;
+                }
+                Err(e) => {
+                    println!("{:>8} failed to load: {}", word_len, e);
+                    failures.push(word_len);
+                    continue;
+                }
+            };
+            let load_time = load_start.elapsed();
+
+            // reversing a word usually makes a non-word so half the lookups should miss
+            let lookups: Vec<String> = dict_chunk
+                .word_set
+                .iter()
+                .flat_map(|word| vec![word.clone(), word.chars().rev().collect()])
+                .collect();
+
+            let lookup_start = std::time::Instant::now();
+            let found_count = lookups.iter().filter(|w| dict_chunk.is_word(w)).count();
+            let lookup_time = lookup_start.elapsed();
+            assert!(found_count >= dict_chunk.word_set.len());
+
+            println!(
+                "{:>8} {:>8} {:>12.2} {:>12.2} {:>14.1}",
+                word_len,
+                dict_chunk.word_set.len(),
+                load_time.as_secs_f64() * 1000.0,
+                lookup_time.as_secs_f64() * 1000.0,
+                lookup_time.as_nanos() as f64 / std::cmp::max(lookups.len(), 1) as f64,
+            );
+        }
+
+        // make sure the bench actually timed every dictionary chunk
+        assert!(
+            failures.is_empty(),
+            "word lengths which failed to load: {:?}",
+            failures
+        );
+    }
 }
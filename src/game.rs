@@ -1,8 +1,5 @@
 // Extended work breakdown
 // - async dict load to hide loading times
-// - support a better interface for providing the input to the solver
-// - add timed mode
-// - add extra game rules for handling selecting brackets?
 // - use appropriate font to give it a "fallout feel"
 // - use appropriate animations to give it a "fallout feel"
 // - SFX
@@ -10,62 +7,68 @@
 // - improve TUI navigation logic to be more intuitive
 // - address all cleanup/refactoring todos
 
-use crate::dict::dict::EnglishDictChunk;
-use crate::utils::str_utils::matching_char_count_ignore_case;
-use crate::utils::tui::{ascii_keycodes, Rect};
+use crate::input::{InputCmd, KeyBindings};
+use crate::recording::Recording;
+use crate::tui::{render_too_small_message, Attrs, MouseAction, Rect, Terminal};
+use fonv_cracker::board::{Board, Difficulty, GenerateErr, PaneSize};
+use fonv_cracker::dict::dict::{DictSource, EnglishDictChunk};
+use fonv_cracker::game_state::{GameCmd, GameEvent, GameOutcome, GameState, SelectedChunk};
+use fonv_cracker::profile::DifficultyProfile;
+use fonv_cracker::utils::seeded_rng::SeededRangeRng;
 use snm_rand_utils::range_rng::{RangeRng, ThreadRangeRng};
 
-const MAX_ATTEMPTS: usize = 4;
+// Seeds picked for unseeded games are kept short so they're easy to read off the screen and share
+const MAX_RANDOM_SEED: usize = 1_000_000_000;
 
-#[derive(Debug, Clone, Copy)]
-pub enum Difficulty {
-    VeryEasy,
-    Easy,
-    Average,
-    Hard,
-    VeryHard,
+pub fn random_seed() -> u64 {
+    ThreadRangeRng::new().gen_range(0, MAX_RANDOM_SEED) as u64
 }
 
-impl std::str::FromStr for Difficulty {
-    type Err = &'static str;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.eq_ignore_ascii_case("VeryEasy") || s.eq_ignore_ascii_case("VE") {
-            return Ok(Difficulty::VeryEasy);
-        }
+// How a game went, handed back once it's over
+pub struct GameResult {
+    pub difficulty: Difficulty,
+    pub outcome: GameOutcome,
+    pub time_limit: Option<std::time::Duration>,
+    pub duration: std::time::Duration,
+    pub guesses: Vec<String>, // every word selected, in order
+    pub attempts_used: usize, // since the attempts were last reset
+    pub board: Board,         // as it was before any duds were removed
+    pub recording: Recording,
+}
 
-        if s.eq_ignore_ascii_case("Easy") || s.eq_ignore_ascii_case("E") {
-            return Ok(Difficulty::Easy);
-        }
+// Input for the game. Replays hand back the game's own commands since the screen positions of
+// recorded mouse clicks would mean nothing once the window has been resized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameInput {
+    Player(InputCmd),
+    Replayed(GameCmd),
+}
 
-        if s.eq_ignore_ascii_case("Average") || s.eq_ignore_ascii_case("A") {
-            return Ok(Difficulty::Average);
-        }
+// Where the game gets its input from each frame
+pub trait InputSource {
+    fn poll_input(&mut self) -> Option<GameInput>;
+}
 
-        if s.eq_ignore_ascii_case("Hard") || s.eq_ignore_ascii_case("H") {
-            return Ok(Difficulty::Hard);
-        }
+// Input from the player's keyboard
+pub struct WindowInputSource<'a> {
+    key_bindings: &'a KeyBindings,
+    window: &'a dyn Terminal,
+}
 
-        if s.eq_ignore_ascii_case("VeryHard") || s.eq_ignore_ascii_case("VH") {
-            return Ok(Difficulty::VeryHard);
+impl<'a> WindowInputSource<'a> {
+    pub fn new(key_bindings: &'a KeyBindings, window: &'a dyn Terminal) -> Self {
+        WindowInputSource {
+            key_bindings,
+            window,
         }
-
-        Err("Invalid difficulty string")
     }
 }
 
-enum Movement {
-    Left,
-    Right,
-    Up,
-    Down,
-}
-
-//#[derive(PartialEq, Eq)]
-enum InputCmd {
-    Move(Movement),
-    Select,
-    Quit,
+impl<'a> InputSource for WindowInputSource<'a> {
+    fn poll_input(&mut self) -> Option<GameInput> {
+        let key = self.window.poll_key()?;
+        self.key_bindings.input_cmd(key).map(GameInput::Player)
+    }
 }
 
 // TODO: should this be split out into two structs?
@@ -78,6 +81,20 @@ struct HexDumpPane {
 }
 
 impl HexDumpPane {
+    const fn new(dump_width: i32, dump_height: i32) -> Self {
+        HexDumpPane {
+            dump_width,
+            dump_height,
+            // TODO: update this to not be characters but bytes or bits or something
+            addr_width: "0x1234".len() as i32, // 2 byte memaddr
+            addr_to_dump_padding: 4, // horizontal padding between panes in the memdump window
+        }
+    }
+
+    fn for_pane_size(pane_size: &PaneSize) -> Self {
+        HexDumpPane::new(pane_size.width as i32, pane_size.height as i32)
+    }
+
     const fn width(&self) -> i32 {
         self.dump_width
     }
@@ -103,249 +120,100 @@ impl HexDumpPane {
     }
 }
 
-// TODO: this chunk selection logic is pretty ugly. Can it be refactored for readability?
-#[derive(Debug, PartialEq, Eq)]
-struct SelectedChunk {
-    pane_num: usize,
-    row_num: usize,
-    col_start: usize,
-    len: usize,
-}
-
-// H_amming D_istance D_istribution Entry
-#[derive(Clone, Copy)]
-struct HDDEntry {
-    num_words: usize, // the number of words to look for with this hamming distance
-    hamming_distance: usize, // the hamming distance to look for
+const HEADER_HEIGHT: i32 = 5;
+const MIN_HEADER_WIDTH: i32 = 66; // the title and the longest seed
+const HISTORY_WIDTH: i32 = 20; // 20 provides enough room for any selected word
+const HISTORY_BOTTOM_PADDING: i32 = 4; // provides a nice padding from the bottom
+const MIN_HISTORY_HEIGHT: i32 = 8;
+
+// (width, height)
+fn min_game_window_size(hex_dump_pane: &HexDumpPane) -> (i32, i32) {
+    let panes_width = hex_dump_pane.full_width() * 2 + hex_dump_pane.padding() * 2;
+    let body_height = hex_dump_pane
+        .height()
+        .max(HISTORY_BOTTOM_PADDING + MIN_HISTORY_HEIGHT);
+    (
+        (panes_width + HISTORY_WIDTH).max(MIN_HEADER_WIDTH),
+        HEADER_HEIGHT + body_height,
+    )
 }
 
-fn get_hamming_distance_distribution(difficulty: Difficulty) -> [HDDEntry; 4] {
-    let distances = match difficulty {
-        Difficulty::VeryEasy => [1, 2, 3, 4],
-        Difficulty::Easy => [1, 3, 4, 5],
-        Difficulty::Average => [1, 3, 5, 7],
-        Difficulty::Hard => [1, 4, 6, 9],
-        Difficulty::VeryHard => [1, 3, 7, 10],
-    };
-
-    [
-        HDDEntry {
-            num_words: 1,
-            hamming_distance: distances[0],
-        },
-        HDDEntry {
-            num_words: 2,
-            hamming_distance: distances[1],
-        },
-        HDDEntry {
-            num_words: 3,
-            hamming_distance: distances[2],
-        },
-        HDDEntry {
-            num_words: 5,
-            hamming_distance: distances[3],
-        },
-    ]
-}
-
-fn get_word_len_for_difficulty(difficulty: Difficulty) -> usize {
-    match difficulty {
-        Difficulty::VeryEasy => 4,
-        Difficulty::Easy => 6,
-        Difficulty::Average => 8,
-        Difficulty::Hard => 10,
-        Difficulty::VeryHard => 12,
-    }
-}
-
-fn generate_words(
-    dict_chunk: &EnglishDictChunk,
-    hd_distribution: &[HDDEntry; 4],
-    rng: &mut dyn RangeRng<usize>,
-) -> (Vec<String>, String) {
-    let total_words_in_distribution = hd_distribution.iter().fold(0, |acc, e| acc + e.num_words);
-
-    let mut words = Vec::with_capacity(total_words_in_distribution + 1);
-    let goal_word = dict_chunk.get_random_word(rng);
-    words.push(goal_word.clone());
-
-    let mut current_hd_distribution_index = 0;
-    let mut hd_distribution_tracker: [HDDEntry; 4] = hd_distribution.clone();
-    let mut hamming_distance_sorted_iter = dict_chunk.get_hamming_distance_sorted_words(&goal_word);
-
-    while current_hd_distribution_index < hd_distribution_tracker.len() {
-        let current_hd_distribution_entry =
-            &mut hd_distribution_tracker[current_hd_distribution_index];
-        assert_ne!(current_hd_distribution_entry.num_words, 0);
-
-        let next_sorted_word_pair = hamming_distance_sorted_iter.next();
-        let (word, hamming_distance) = match next_sorted_word_pair {
-            None => break, // we are out of words!
-            Some(sorted_word_pair) => sorted_word_pair,
-        };
-
-        if hamming_distance >= current_hd_distribution_entry.hamming_distance {
-            current_hd_distribution_entry.num_words -= 1;
-            words.push(String::from(word));
-
-            if current_hd_distribution_entry.num_words == 0 {
-                current_hd_distribution_index += 1;
-            }
-        }
-    }
-
-    // the code can manage finding fewer words, but this represents a bug
-    assert_eq!(words.len(), total_words_in_distribution + 1);
-    (words, goal_word)
+// Where each part of the game screen goes. The header runs across the top, the hex dump panes sit
+// side by side under it and the history runs up the right hand side.
+#[derive(Debug, PartialEq, Eq)]
+struct GameLayout {
+    header: Rect,
+    hex_dump_rects: [Rect; 2],
+    history: Rect,
 }
 
-fn simple_shuffle<T>(mut v: Vec<T>, rng: &mut dyn RangeRng<usize>) -> Vec<T> {
-    const NUM_SWAPS: usize = 100; // a good-enough heuristic for shuffling the words in place
-
-    for _ in 0..NUM_SWAPS {
-        let index = rng.gen_range(0, v.len());
-        v.swap(0, index);
+fn layout_game_window(window_rect: Rect, hex_dump_pane: &HexDumpPane) -> Option<GameLayout> {
+    let (min_width, min_height) = min_game_window_size(hex_dump_pane);
+    if !window_rect.fits(min_width, min_height) {
+        return None;
     }
 
-    v
-}
+    let (header, body) = window_rect.split_top(HEADER_HEIGHT);
+    let (hex_dump_area, history_column) = body.split_right(HISTORY_WIDTH);
+    let (history, _) = history_column.split_bottom(HISTORY_BOTTOM_PADDING);
 
-fn generate_words_from_difficulty(
-    difficulty: Difficulty,
-    rng: &mut dyn RangeRng<usize>,
-) -> (Vec<String>, String) {
-    let dict_chunk = EnglishDictChunk::load(get_word_len_for_difficulty(difficulty));
-    let hd_distribution = get_hamming_distance_distribution(difficulty);
-    generate_words(&dict_chunk, &hd_distribution, rng)
-}
-
-fn move_selection(
-    selection: SelectedChunk,
-    movement: Movement,
-    hex_dump_pane_dimensions: &HexDumpPane,
-    num_panes: usize,
-) -> SelectedChunk {
-    let (col_move, row_move): (i32, i32) = match movement {
-        Movement::Down => (0, 1),
-        Movement::Up => (0, -1),
-        Movement::Left => (-1, 0),
-        // We might be at the beginning of a full word, in which case move past the end of the word
-        Movement::Right => (selection.len as i32, 0),
+    let pane_area = Rect {
+        height: hex_dump_pane.height(),
+        ..hex_dump_area
     };
-
-    // Naively update the row and col with our movement
-    let mut next_col = col_move + (selection.col_start as i32);
-    let mut next_row = row_move + (selection.row_num as i32);
-    let mut next_pane = selection.pane_num as i32;
-
-    // Check if we've moved from one pane to another by moving laterally across the columns in a row.
-    if next_col >= hex_dump_pane_dimensions.width() {
-        next_col = 0;
-        next_pane += 1;
-    } else if next_col < 0 {
-        next_col = hex_dump_pane_dimensions.width() - 1;
-        next_pane -= 1;
-    }
-
-    // Check if we've moved to an invalid row outside of our pane.
-    // In which case just wrap around to the next valid row in the same pane.
-    if next_row >= hex_dump_pane_dimensions.height() {
-        next_row = 0;
-    } else if next_row < 0 {
-        next_row = hex_dump_pane_dimensions.height() - 1;
-    }
-
-    // Check if we've moved to an invalid pane outside of our hex dump.
-    // In which case just wrap around to the next valid pane.
-    if next_pane >= num_panes as i32 {
-        next_pane = 0;
-    } else if next_pane < 0 {
-        next_pane = 1;
-    }
-
-    SelectedChunk {
-        pane_num: next_pane as usize,
-        row_num: next_row as usize,
-        col_start: next_col as usize,
-        len: 1,
-    }
+    let (left_hex_dump_rect, rest) = pane_area.split_left(hex_dump_pane.full_width());
+    let (_, rest) = rest.split_left(hex_dump_pane.padding());
+    let (right_hex_dump_rect, _) = rest.split_left(hex_dump_pane.full_width());
+
+    Some(GameLayout {
+        header,
+        hex_dump_rects: [left_hex_dump_rect, right_hex_dump_rect],
+        history,
+    })
 }
 
-fn refit_selection<S: AsRef<str>>(
-    selection: SelectedChunk,
-    words: &[S],
-    word_offsets: &[usize],
-    hex_dump_pane_dimensions: &HexDumpPane,
-) -> SelectedChunk {
-    let cursor_index = selection.pane_num * hex_dump_pane_dimensions.max_bytes_in_pane()
-        + selection.row_num * hex_dump_pane_dimensions.width() as usize
-        + selection.col_start;
-
-    // TODO: clean up this implementation. It's a bit ugly
-    let mut result_selection = selection;
-
-    // turn our list of words and word_offsets into a list of ranges where those words live
-    // in the contiguous hex dump memory span
-    let word_ranges = words
-        .iter()
-        .zip(word_offsets.iter())
-        .map(|(word, word_offset)| (*word_offset, word_offset + word.as_ref().len()));
-
-    for word_range in word_ranges {
-        if cursor_index >= word_range.0 && cursor_index < word_range.1 {
-            // if our cursor is on or in the middle of a full word, update the cursor selection
-            // to highlight the whole word
-            let cursor_offset = cursor_index - word_range.0;
-            if cursor_offset <= result_selection.col_start {
-                result_selection.col_start -= cursor_offset;
-            } else {
-                // account for the word starting on the previous row
-                // account for the previous row, being in the previous pane
-                if result_selection.row_num > 0 {
-                    result_selection.row_num -= 1;
-                } else {
-                    result_selection.row_num = (hex_dump_pane_dimensions.height() - 1) as usize;
-                    result_selection.pane_num -= 1;
+impl GameLayout {
+    // The character of the hex dump at a spot on the screen, if the spot isn't off in an address
+    // column or the space around the panes
+    fn hex_dump_chunk_at(
+        &self,
+        hex_dump_dimensions: &HexDumpPane,
+        y: i32,
+        x: i32,
+    ) -> Option<SelectedChunk> {
+        let addr_and_padding_width =
+            hex_dump_dimensions.addr_width() as i32 + hex_dump_dimensions.padding();
+        self.hex_dump_rects
+            .iter()
+            .enumerate()
+            .find_map(|(pane_num, hex_dump_rect)| {
+                let (_, dump_rect) = hex_dump_rect.split_left(addr_and_padding_width);
+                if !dump_rect.contains(y, x) {
+                    return None;
                 }
-                result_selection.col_start +=
-                    hex_dump_pane_dimensions.width() as usize - cursor_offset;
-            }
-            result_selection.len = word_range.1 - word_range.0;
-            break;
-        }
-    }
-
-    result_selection
-}
 
-fn try_select_word<'a, S: AsRef<str>>(
-    selection: &SelectedChunk,
-    words: &'a [S],
-    word_offsets: &[usize],
-    hex_dump_pane_dimensions: &HexDumpPane,
-) -> Option<&'a str> {
-    let cursor_index = selection.pane_num * hex_dump_pane_dimensions.max_bytes_in_pane()
-        + selection.row_num * hex_dump_pane_dimensions.width() as usize
-        + selection.col_start;
-
-    for (word, word_offset) in words.iter().zip(word_offsets.iter()) {
-        if cursor_index >= *word_offset && cursor_index < word_offset + word.as_ref().len() {
-            // For safety we'll return the word if the cursor is anywhere in the word selection,
-            // but we only really expect it to be at the start of the word.
-            assert_eq!(cursor_index, *word_offset);
-            assert_eq!(selection.len, word.as_ref().len());
-            return Some(word.as_ref());
-        }
+                Some(SelectedChunk {
+                    pane_num,
+                    row_num: (y - dump_rect.top) as usize,
+                    col_start: (x - dump_rect.left) as usize,
+                    len: 1,
+                })
+            })
     }
+}
 
-    None
+// An entry in the selection history pane, listed oldest first
+enum HistoryEntry {
+    Denied(String, usize),
+    DudRemoved(String),
+    TriesReset(String),
 }
 
 // TODO: this chunk render function is pretty nasty. can it be refactored better readability
 // one idea is to split out the rendering of memory addresses from rendering out the actual hex dumps
 //   this could help greatly clean up some offset calculations...
 fn render_hexdump_pane(
-    window: &pancurses::Window,
+    window: &dyn Terminal,
     hex_dump_dimensions: &HexDumpPane,
     render_rect: &Rect,
     hex_dump_first_byte: usize,
@@ -374,811 +242,612 @@ fn render_hexdump_pane(
         for (byte, col_index) in byte_at_cols {
             let byte_offset = row_first_byte + col_index as usize;
             if byte_offset >= highlighted_byte_start && byte_offset < highlighted_byte_end {
-                window.attron(pancurses::A_BLINK);
+                window.attron(Attrs::BLINK);
             } else {
-                window.attroff(pancurses::A_BLINK);
+                window.attroff(Attrs::BLINK);
             }
             window.mvaddch(y, begin_dump_offset + col_index, byte);
         }
-        window.attroff(pancurses::A_BLINK);
+        window.attroff(Attrs::BLINK);
     }
 }
 
-fn obfuscate_words(
-    words: &[String],
-    target_size: usize,
-    rng: &mut dyn RangeRng<usize>,
-) -> (String, Vec<usize>) {
-    let initial_length_from_words: usize = words.iter().fold(0, |acc, word| acc + word.len());
-    let remaining_char_count_to_generate = target_size - initial_length_from_words;
-
-    // place the words at offsets within the final obfuscated string such that filling in between/around
-    // those words will generate the final obfuscated string
-    let offsets = {
-        let mut offsets = Vec::new();
-        for word in words.iter() {
-            for offset in offsets.iter_mut() {
-                *offset += word.len();
-            }
-            offsets.insert(0, 0);
-        }
-
-        for _ in 0..remaining_char_count_to_generate {
-            // Increment each offset starting from a random offset.
-            // This simulates adding a character before a random word in the final obfuscated string
-            // e.g. if offsets_to_bump_start = 1, then a character is added between words 0 and 1
-            // and words 1->onward are then offset by an additional character.
-            let offsets_to_bump_start = rng.gen_range(0, offsets.len() + 1);
-
-            for i in offsets_to_bump_start..offsets.len() {
-                offsets[i] += 1;
-            }
-        }
-        offsets
-    };
-
-    let mut string_builder = String::with_capacity(target_size);
-
-    // fill the string with the initial garbage chars
-    for _ in 0..remaining_char_count_to_generate {
-        let garbage_char = rng.gen_range('#' as usize, '.' as usize) as u8 as char;
-        string_builder.push(garbage_char);
-    }
-
-    // insert each of the offset words
-    for (word, offset) in words.iter().zip(offsets.iter()) {
-        string_builder.insert_str(*offset, &word);
-    }
-
-    (string_builder, offsets)
-}
-
 fn render_game_window(
-    window: &pancurses::Window,
-    cursor_selection: &SelectedChunk,
-    hex_dump_start_addr: usize,
-    hex_dump: &str,
+    window: &dyn Terminal,
+    game_state: &GameState,
     hex_dump_dimensions: &HexDumpPane,
-    hex_dump_rects: &[Rect],
-    denied_selections: &[(&str, usize)],
-    accepted_selection: &Option<&str>,
+    layout: &GameLayout,
+    seed: u64,
+    history: &[HistoryEntry],
 ) {
-    // Render the hex dump header
-    window.mvaddstr(0, 0, "ROBCO INDUSTRIES (TM) TERMALINK PROTOCOL");
-    window.mvaddstr(1, 0, "ENTER PASSWORD NOW");
+    let board = game_state.board();
+    let max_attempts = game_state.attempts();
+    let time_left = game_state.time_left();
 
+    // Render the hex dump header
+    let header = &layout.header;
+    window.mvaddstr(
+        header.top,
+        header.left,
+        "ROBCO INDUSTRIES (TM) TERMALINK PROTOCOL",
+    );
+    window.mvaddstr(header.top + 1, header.left, "ENTER PASSWORD NOW");
+
+    let seed_text = format!("SEED {}", seed);
+    window.mvaddstr(
+        header.top,
+        header.right() - seed_text.len() as i32,
+        &seed_text,
+    );
+
+    let attempts_row = header.top + 3;
     let attempts_left_title = "# ATTEMPT(S) LEFT:";
-    window.mvaddstr(3, 0, attempts_left_title);
-
-    let total_attempts = {
-        let mut attempts = denied_selections.len();
-        if accepted_selection.is_some() {
-            attempts += 1;
-        }
-        attempts
-    };
+    window.mvaddstr(attempts_row, header.left, attempts_left_title);
 
-    for i in 0..(MAX_ATTEMPTS - total_attempts) {
+    for i in 0..(max_attempts - game_state.attempts_used()) {
         const BLOCK_CHAR_CHUNK: &str = " #";
         let offset = attempts_left_title.len() + i * BLOCK_CHAR_CHUNK.len();
-        window.mvaddstr(3, offset as i32, BLOCK_CHAR_CHUNK);
+        window.mvaddstr(attempts_row, header.left + offset as i32, BLOCK_CHAR_CHUNK);
+    }
+
+    // leave room for every attempt block so the clock doesn't shift as attempts get used
+    if let Some(time_left) = time_left {
+        let offset = attempts_left_title.len() + max_attempts * 2 + 4;
+        // round up so the clock only reads 0:00 once time has actually run out
+        let time_left_secs = (time_left + std::time::Duration::from_millis(999)).as_secs();
+        let time_left_text = format!(
+            "TIME LEFT: {}:{:02}",
+            time_left_secs / 60,
+            time_left_secs % 60
+        );
+        window.mvaddstr(attempts_row, header.left + offset as i32, &time_left_text);
     }
 
     let highlighted_byte_range = {
-        let start = cursor_selection.pane_num * hex_dump_dimensions.max_bytes_in_pane()
-            + cursor_selection.row_num * hex_dump_dimensions.width() as usize
-            + cursor_selection.col_start;
+        let cursor_selection = game_state.selection();
+        let start = cursor_selection.offset(&board.pane_size);
         let end = start + cursor_selection.len;
         (start, end)
     };
 
     // render each hex dump pane (assume ordered left to right)
-    for hex_dump_pane_index in 0..hex_dump_rects.len() {
-        let hex_dump_rect = &hex_dump_rects[hex_dump_pane_index];
+    for hex_dump_pane_index in 0..layout.hex_dump_rects.len() {
+        let hex_dump_rect = &layout.hex_dump_rects[hex_dump_pane_index];
         let pane_byte_offset = hex_dump_pane_index * hex_dump_dimensions.max_bytes_in_pane();
         render_hexdump_pane(
-            &window,
+            window,
             hex_dump_dimensions,
-            &hex_dump_rect,
-            hex_dump_start_addr + pane_byte_offset,
-            &hex_dump,
+            hex_dump_rect,
+            board.start_addr + pane_byte_offset,
+            &board.hex_dump,
             pane_byte_offset,
             highlighted_byte_range,
         );
     }
 
     // Render the selection history
-    let mut row_cursor = window.get_max_y() - 5; // 5 provides a nice padding from the bottom
-    let selection_history_start_col = window.get_max_x() - 20; // 20 provides enough room for any selected word
+    // The oldest entries scroll off the top of the history once it fills up
+    let history_rect = &layout.history;
+    let mut row_cursor = history_rect.bottom() - 1;
 
     let write_history_entries = |row: &mut i32, entries: &[&str]| {
         for entry in entries.iter().rev() {
-            window.mvaddstr(*row, selection_history_start_col, format!(">{}", entry));
+            if *row >= history_rect.top {
+                window.mvaddstr(*row, history_rect.left, &format!(">{}", entry));
+            }
             *row -= 1;
         }
     };
 
     // first render the accepted solution if provided or the failure text if we've lost
-    window.attron(pancurses::A_BLINK);
-    if let Some(accepted_selection) = accepted_selection {
-        let lines = [
-            accepted_selection,
-            "Exact match!",
-            "Please wait",
-            "while system",
-            "is accessed.",
-        ];
-        write_history_entries(&mut row_cursor, &lines);
-    } else if denied_selections.len() == MAX_ATTEMPTS {
-        let lines = ["TOO MANY ATTEMPTS!", "Entering secure", "lock mode"];
-        write_history_entries(&mut row_cursor, &lines);
-    }
-    window.attroff(pancurses::A_BLINK);
-
-    // now render each denied entry
-    for (denied_word, matching_char_count) in denied_selections.iter().rev() {
-        let char_count_str = format!("{}/{} correct.", matching_char_count, denied_word.len());
-        let lines = ["Entry denied", &char_count_str, denied_word];
-        write_history_entries(&mut row_cursor, &lines);
+    window.attron(Attrs::BLINK);
+    match game_state.outcome() {
+        Some(GameOutcome::Won) => {
+            let lines = [
+                board.solution.as_str(),
+                "Exact match!",
+                "Please wait",
+                "while system",
+                "is accessed.",
+            ];
+            write_history_entries(&mut row_cursor, &lines);
+        }
+        Some(GameOutcome::Lost) => {
+            let lines = ["TOO MANY ATTEMPTS!", "Entering secure", "lock mode"];
+            write_history_entries(&mut row_cursor, &lines);
+        }
+        Some(GameOutcome::TimedOut) => {
+            let lines = ["TIME EXPIRED!", "Entering secure", "lock mode"];
+            write_history_entries(&mut row_cursor, &lines);
+        }
+        Some(GameOutcome::Quit) | None => (),
+    }
+    window.attroff(Attrs::BLINK);
+
+    // now render the rest of the history, newest first
+    for entry in history.iter().rev() {
+        match entry {
+            HistoryEntry::Denied(denied_word, matching_char_count) => {
+                let char_count_str =
+                    format!("{}/{} correct.", matching_char_count, denied_word.len());
+                let lines = ["Entry denied", &char_count_str, denied_word];
+                write_history_entries(&mut row_cursor, &lines);
+            }
+            HistoryEntry::DudRemoved(brackets) => {
+                write_history_entries(&mut row_cursor, &[brackets, "Dud removed."]);
+            }
+            HistoryEntry::TriesReset(brackets) => {
+                write_history_entries(&mut row_cursor, &[brackets, "Tries reset."]);
+            }
+        }
     }
 }
 
-pub fn run_game(difficulty: Difficulty, window: &pancurses::Window) {
-    const HEX_DUMP_PANE: HexDumpPane = HexDumpPane {
-        dump_width: 12,  // 12 characters per row of the hexdump
-        dump_height: 16, // 16 rows of hex dump per dump pane
-        // TODO: update this to not be characters but bytes or bits or something
-        addr_width: "0x1234".len() as i32, // 2 byte memaddr
-        addr_to_dump_padding: 4,           // horizontal padding between panes in the memdump window
-    };
-
-    const HEXDUMP_PANE_VERT_OFFSET: i32 = 5;
-
-    let hex_dump_rects = {
-        let left_hex_dump_rect = Rect {
-            left: 0,
-            top: HEXDUMP_PANE_VERT_OFFSET,
-            width: HEX_DUMP_PANE.full_width(),
-            height: HEX_DUMP_PANE.height(),
-        };
-
-        let right_hex_dump_rect = Rect {
-            left: left_hex_dump_rect.width + HEX_DUMP_PANE.padding(),
-            top: left_hex_dump_rect.top,
-            width: HEX_DUMP_PANE.full_width(),
-            height: HEX_DUMP_PANE.height(),
-        };
-
-        [left_hex_dump_rect, right_hex_dump_rect]
+// Play a game to the end and hand back how it went along with a recording of it which can be saved
+// and replayed later. A loaded board is played as is, otherwise one is generated from the profile.
+pub fn run_game(
+    profile: &DifficultyProfile,
+    loaded_board: Option<Board>,
+    seed: Option<u64>,
+    time_limit: Option<std::time::Duration>,
+    dict_source: &DictSource,
+    input_source: &mut dyn InputSource,
+    window: &dyn Terminal,
+) -> Result<GameResult, GenerateErr> {
+    // Everything random about the board comes from the seed so the same seed and difficulty always
+    // generate the same board
+    let seed = seed.unwrap_or_else(random_seed);
+    let mut rng = SeededRangeRng::new(seed);
+    let board = match &loaded_board {
+        Some(board) => board.clone(),
+        None => {
+            let dict_chunk = EnglishDictChunk::load(dict_source, profile.word_len)
+                .map_err(GenerateErr::LoadDict)?;
+            Board::generate(profile, &dict_chunk, &mut rng)?
+        }
     };
+    let initial_board = board.clone();
+    let hex_dump_pane = HexDumpPane::for_pane_size(&board.pane_size);
 
-    // Generate a random set of words based on the provided difficulty setting
-    let mut rng = ThreadRangeRng::new();
-    let (unshuffled_words, solution) = generate_words_from_difficulty(difficulty, &mut rng);
-    assert_eq!(unshuffled_words.len(), 12); // the game isn't broken if we don't have 12 words but it represents a bug
-    let words = simple_shuffle(unshuffled_words, &mut rng);
-
-    let mut denied_selections = Vec::new();
-    let mut accepted_selection = None;
-    fn is_game_over(
-        denied_selections: &[(&str, usize)],
-        accepted_selection: &Option<&str>,
-    ) -> bool {
-        denied_selections.len() == MAX_ATTEMPTS || accepted_selection.is_some()
-    }
+    // The bracket sequences carry on with the board's rng so the whole game stays seeded
+    let mut game_state = GameState::new(board, profile.attempts, time_limit, Box::new(rng));
+    let mut history = Vec::new();
     const GAME_OVER_HOLD_TIME: std::time::Duration = std::time::Duration::from_secs(3);
     let mut game_over_timer = None;
 
-    // Generate a mock hexdump from the randomly generated words
-    const MAX_BYTES_IN_DUMP: usize = HEX_DUMP_PANE.max_bytes_in_pane() * 2; // 2 dump panes
-    let (hex_dump, word_offsets) = obfuscate_words(&words, MAX_BYTES_IN_DUMP, &mut rng);
-
-    // For visual flair, randomize the mem address of the hex dump
-    const MIN_MEMADDR: usize = 0xCC00;
-    const MAX_MEMADDR: usize = 0xFFFF - MAX_BYTES_IN_DUMP;
-    const_assert!(MIN_MEMADDR < MAX_MEMADDR);
-    let hex_dump_start_addr = rng.gen_range(MIN_MEMADDR, MAX_MEMADDR);
-
-    // initially select the first character in the row pane
-    let mut selected_chunk = SelectedChunk {
-        pane_num: 0,
-        row_num: 0,
-        col_start: 0,
-        len: 1,
-    };
-
-    // Immediately refit the selection in case the first character is part of a larger word
-    selected_chunk = refit_selection(selected_chunk, &words, &word_offsets, &HEX_DUMP_PANE);
+    // The board is always kept in the recording since generated ones also depend on the dictionary
+    let mut recording = Recording::new(
+        profile.clone(),
+        Some(initial_board.clone()),
+        seed,
+        time_limit,
+    );
+    // Where the mouse leaves the cursor is only recorded once a command depends on it so just
+    // hovering over the hex dump doesn't end up in the recording
+    let mut cursor_moved_by_mouse = false;
+    let game_start = std::time::Instant::now();
+
+    let mut layout_window_rect = window.rect();
+    let mut layout = layout_game_window(layout_window_rect, &hex_dump_pane);
+
+    // Frames are scheduled off the clock rather than by sleeping a fixed amount after each one so
+    // slow frames don't make the countdown drift
+    const FRAME_DURATION: std::time::Duration = std::time::Duration::from_millis(33);
+    let mut next_frame_time = game_start;
 
-    // TODO: refactor this loop for readability and testing
     loop {
-        // Poll for input
-        let polled_input_cmd = match window.getch() {
-            Some(pancurses::Input::Character('w')) => Some(InputCmd::Move(Movement::Up)),
-            Some(pancurses::Input::Character('s')) => Some(InputCmd::Move(Movement::Down)),
-            Some(pancurses::Input::Character('a')) => Some(InputCmd::Move(Movement::Left)),
-            Some(pancurses::Input::Character('d')) => Some(InputCmd::Move(Movement::Right)),
-            Some(pancurses::Input::Character(ascii_keycodes::ESC)) => Some(InputCmd::Quit),
-            Some(pancurses::Input::Character(ascii_keycodes::ENTER))
-            | Some(pancurses::Input::KeyEnter) => Some(InputCmd::Select),
-            _ => None,
-        };
-
-        // Handle the input
-        if let Some(input_cmd) = polled_input_cmd {
-            match input_cmd {
-                // Handle moving the cursor around the hex dump pane
-                InputCmd::Move(movement) => {
-                    // Move the cursor based on our input
-                    selected_chunk = move_selection(selected_chunk, movement, &HEX_DUMP_PANE, 2);
-                    // If the cursor is now selecting a word, refit the selection highlight for the whole word
-                    selected_chunk =
-                        refit_selection(selected_chunk, &words, &word_offsets, &HEX_DUMP_PANE);
+        // The clock ticks before any input gets handled so late input can't beat the time limit
+        let mut game_cmds = vec![GameCmd::Tick(game_start.elapsed())];
+
+        // Turn the input into commands for the game
+        let polled_input = input_source.poll_input();
+        let input_time = game_start.elapsed();
+        match polled_input {
+            Some(GameInput::Player(input_cmd)) => match input_cmd {
+                InputCmd::Move(movement) => game_cmds.push(GameCmd::Move(movement)),
+
+                // Handle the mouse pointing at or clicking on the hex dump
+                InputCmd::Mouse(event) => {
+                    let pointed_at_chunk = layout.as_ref().and_then(|layout| {
+                        layout.hex_dump_chunk_at(&hex_dump_pane, event.y, event.x)
+                    });
+                    if let Some(pointed_at_chunk) = pointed_at_chunk {
+                        game_cmds.push(GameCmd::MoveTo {
+                            pane_num: pointed_at_chunk.pane_num,
+                            row_num: pointed_at_chunk.row_num,
+                            col: pointed_at_chunk.col_start,
+                        });
+                        if event.action == MouseAction::Click {
+                            game_cmds.push(GameCmd::Select);
+                        }
+                    }
                 }
 
-                // Handle selecting a word
-                InputCmd::Select => {
-                    if !is_game_over(&denied_selections, &accepted_selection) {
-                        let selected_word_result =
-                            try_select_word(&selected_chunk, &words, &word_offsets, &HEX_DUMP_PANE);
-                        if let Some(selected_word) = selected_word_result {
-                            if selected_word == solution {
-                                accepted_selection = Some(selected_word);
-                            } else {
-                                let matching_char_count =
-                                    matching_char_count_ignore_case(&solution, &selected_word);
-                                denied_selections.push((selected_word, matching_char_count));
-                            }
-                        }
+                InputCmd::Select => game_cmds.push(GameCmd::Select),
+                InputCmd::Quit => game_cmds.push(GameCmd::Quit),
+            },
+            Some(GameInput::Replayed(game_cmd)) => game_cmds.push(game_cmd),
+            None => (),
+        }
 
-                        if is_game_over(&denied_selections, &accepted_selection) {
-                            game_over_timer = Some(std::time::Instant::now());
-                        }
+        let mut quit = false;
+        for game_cmd in game_cmds {
+            match game_cmd {
+                GameCmd::MoveTo { .. } => cursor_moved_by_mouse = true,
+                GameCmd::Move(_) | GameCmd::Select => {
+                    if cursor_moved_by_mouse {
+                        let selection = game_state.selection();
+                        recording.push_input(
+                            input_time,
+                            GameCmd::MoveTo {
+                                pane_num: selection.pane_num,
+                                row_num: selection.row_num,
+                                col: selection.col_start,
+                            },
+                        );
+                        cursor_moved_by_mouse = false;
                     }
+                    recording.push_input(input_time, game_cmd);
                 }
+                GameCmd::Quit => recording.push_input(input_time, game_cmd),
+                GameCmd::Tick(_) => (),
+            }
 
-                // Handle quitting the game early
-                InputCmd::Quit => break,
+            quit |= game_cmd == GameCmd::Quit;
+            for event in game_state.handle(game_cmd) {
+                match event {
+                    GameEvent::EntryDenied { word, likeness } => {
+                        history.push(HistoryEntry::Denied(word, likeness))
+                    }
+                    GameEvent::DudRemoved(brackets) => {
+                        history.push(HistoryEntry::DudRemoved(brackets))
+                    }
+                    GameEvent::TriesReset(brackets) => {
+                        history.push(HistoryEntry::TriesReset(brackets))
+                    }
+                    GameEvent::AccessGranted(_) | GameEvent::LockedOut | GameEvent::TimedOut => {
+                        game_over_timer = Some(std::time::Instant::now())
+                    }
+                }
             }
         }
 
+        // Handle quitting the game early
+        if quit {
+            break;
+        }
+
+        // Lay the screen out again whenever the window has been resized
+        let window_rect = window.rect();
+        if window_rect != layout_window_rect {
+            layout_window_rect = window_rect;
+            layout = layout_game_window(window_rect, &hex_dump_pane);
+        }
+
         // Render the next frame
-        window.erase();
-        render_game_window(
-            &window,
-            &selected_chunk,
-            hex_dump_start_addr,
-            &hex_dump,
-            &HEX_DUMP_PANE,
-            &hex_dump_rects,
-            &denied_selections,
-            &accepted_selection,
-        );
-        window.refresh();
+        match &layout {
+            Some(layout) => {
+                window.erase();
+                render_game_window(window, &game_state, &hex_dump_pane, layout, seed, &history);
+                window.refresh();
+            }
+            None => {
+                let (min_width, min_height) = min_game_window_size(&hex_dump_pane);
+                render_too_small_message(window, min_width, min_height);
+            }
+        }
 
         // No need to waste cycles doing nothing but rendering over and over.
         // Yield the processor until the next frame.
-        std::thread::sleep(std::time::Duration::from_millis(33));
+        next_frame_time += FRAME_DURATION;
+        let now = std::time::Instant::now();
+        if next_frame_time > now {
+            std::thread::sleep(next_frame_time - now);
+        } else {
+            // we fell behind so start scheduling again from now rather than rushing to catch up
+            next_frame_time = now;
+        }
 
         // If the game is over and we've been staring at the screen for long enough exit
         if game_over_timer.is_some() && game_over_timer.unwrap().elapsed() >= GAME_OVER_HOLD_TIME {
             break;
         }
     }
+
+    Ok(GameResult {
+        difficulty: profile.difficulty,
+        outcome: game_state.outcome().unwrap_or(GameOutcome::Quit),
+        time_limit,
+        duration: game_state.duration(),
+        guesses: game_state.guesses().to_vec(),
+        attempts_used: game_state.attempts_used(),
+        board: initial_board,
+        recording,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use snm_rand_utils::mocks as rand_mocks;
-
-    #[test]
-    fn test_word_generation() {
-        // use a single-value rng for value 0. This will make sure the goal_word is the first word in the original word list
-        let mut rng = rand_mocks::SingleValueRangeRng::new(0);
-
-        let test_hd_distribution = [
-            HDDEntry {
-                num_words: 1,
-                hamming_distance: 1,
-            },
-            HDDEntry {
-                num_words: 2,
-                hamming_distance: 2,
-            },
-            HDDEntry {
-                num_words: 3,
-                hamming_distance: 3,
-            },
-            HDDEntry {
-                num_words: 4,
-                hamming_distance: 4,
-            },
-        ];
-
-        let goal_word = "dude";
-        let words = [
-            goal_word, // 0
-            "dede",    // 1
-            "door",    // 3
-            "dodo",    // 2
-            "doom",    // 3
-            "abba",    // 4
-            "rude",    // 1
-            "duds",    // 1
-            "rube",    // 2
-            "cube",    // 2
-            "sick",    // 4
-            "stop",    // 4
-            "soil",    // 4
-            "roll",    // 4
-        ];
-
-        let expected_generated_words = [
-            goal_word, // goal
-            "dede",    // hd 1
-            "dodo", "rube", // hd 2
-            "door", "doom", "abba", // hd 3
-            "sick", "stop", "soil", "roll", // hd 4
-        ];
-
-        let test_dict = EnglishDictChunk::new_mock(4, &words);
-        let (generated_words, solution) =
-            generate_words(&test_dict, &test_hd_distribution, &mut rng);
-
-        assert_eq!(solution, goal_word);
-        assert_eq!(generated_words, expected_generated_words);
-    }
-
-    #[test]
-    fn test_obfuscate_words() {
-        let mut rng = ThreadRangeRng::new();
-        let words: Vec<String> = ["apple", "orange", "banana"]
-            .iter()
-            .map(|s| String::from(*s))
-            .collect();
-
-        const HEX_BYTE_COUNT: usize = 100;
-        let (obfuscated_words, offsets) = obfuscate_words(&words, HEX_BYTE_COUNT, &mut rng);
-
-        assert_eq!(HEX_BYTE_COUNT, obfuscated_words.len());
-        for (word, word_offset) in words.iter().zip(offsets.iter()) {
-            let word_in_blob = &obfuscated_words[*word_offset..][..word.len()];
-            assert_eq!(word, word_in_blob);
-        }
-    }
+    use crate::tui::headless::HeadlessTerminal;
+    use crate::tui::MouseEvent;
+    use fonv_cracker::board::MAX_ATTEMPTS;
+    use fonv_cracker::game_state::Movement;
 
-    fn move_and_refit(
-        mut selection: SelectedChunk,
-        movement: Movement,
-        words: &[&str],
-        word_offsets: &[usize],
-        hex_dump_pane_dimensions: &HexDumpPane,
-        num_panes: usize,
-    ) -> SelectedChunk {
-        selection = move_selection(selection, movement, &hex_dump_pane_dimensions, num_panes);
-        refit_selection(selection, &words, &word_offsets, &hex_dump_pane_dimensions)
-    }
+    // the size every preset difficulty uses
+    const HEX_DUMP_PANE: HexDumpPane = HexDumpPane::new(12, 16);
 
     #[test]
-    fn test_single_char_move_next() {
-        // .... ....
-        // .abc .xyz
-        // .... ....
-        //  ^^
-        let start_selection = SelectedChunk {
-            pane_num: 0,
-            row_num: 2,
-            col_start: 1,
-            len: 1,
-        };
-        let expected_end_selection = SelectedChunk {
-            pane_num: 0,
-            row_num: 2,
-            col_start: 2,
-            len: 1,
-        };
-        let movement = Movement::Right;
-        let words = ["abc", "xyz"];
-        let word_offsets = [5, 17];
-        let hex_dump_pane_dimensions = HexDumpPane {
-            dump_width: 4,
-            dump_height: 3,
-            addr_width: 0,           // unused
-            addr_to_dump_padding: 0, // unused
-        };
-
-        let end_selection = move_and_refit(
-            start_selection,
-            movement,
-            &words,
-            &word_offsets,
-            &hex_dump_pane_dimensions,
-            2,
+    fn test_layout_game_window() {
+        let window_rect = |width, height| Rect {
+            left: 0,
+            top: 0,
+            width,
+            height,
+        };
+
+        // a standard terminal keeps the panes where they've always been
+        let layout = layout_game_window(window_rect(80, 24), &HEX_DUMP_PANE).unwrap();
+        assert_eq!(layout.header, window_rect(80, 5));
+        assert_eq!(
+            layout.hex_dump_rects,
+            [
+                Rect {
+                    left: 0,
+                    top: 5,
+                    width: 22,
+                    height: 16,
+                },
+                Rect {
+                    left: 26,
+                    top: 5,
+                    width: 22,
+                    height: 16,
+                },
+            ]
         );
-
-        assert_eq!(end_selection, expected_end_selection);
-    }
-
-    #[test]
-    fn test_single_char_move_across_panes_right() {
-        // .... ....
-        // .abc .xyz
-        // .... ....
-        //    ^ ^
-        let start_selection = SelectedChunk {
-            pane_num: 0,
-            row_num: 2,
-            col_start: 3,
-            len: 1,
-        };
-        let expected_end_selection = SelectedChunk {
-            pane_num: 1,
-            row_num: 2,
-            col_start: 0,
-            len: 1,
-        };
-        let movement = Movement::Right;
-        let words = ["abc", "xyz"];
-        let word_offsets = [5, 17];
-        let hex_dump_pane_dimensions = HexDumpPane {
-            dump_width: 4,
-            dump_height: 3,
-            addr_width: 0,           // unused
-            addr_to_dump_padding: 0, // unused
-        };
-
-        let end_selection = move_and_refit(
-            start_selection,
-            movement,
-            &words,
-            &word_offsets,
-            &hex_dump_pane_dimensions,
-            2,
+        assert_eq!(
+            layout.history,
+            Rect {
+                left: 60,
+                top: 5,
+                width: 20,
+                height: 15,
+            }
         );
 
-        assert_eq!(end_selection, expected_end_selection);
-    }
-
-    #[test]
-    fn test_single_char_move_across_panes_left() {
-        // .... ....
-        // .abc .xyz
-        // .... ....
-        // ^       ^
-        let start_selection = SelectedChunk {
-            pane_num: 0,
-            row_num: 2,
-            col_start: 0,
-            len: 1,
-        };
-        let expected_end_selection = SelectedChunk {
-            pane_num: 1,
-            row_num: 2,
-            col_start: 3,
-            len: 1,
-        };
-        let movement = Movement::Left;
-        let words = ["abc", "xyz"];
-        let word_offsets = [5, 17];
-        let hex_dump_pane_dimensions = HexDumpPane {
-            dump_width: 4,
-            dump_height: 3,
-            addr_width: 0,           // unused
-            addr_to_dump_padding: 0, // unused
-        };
+        // the history follows the right edge of the window
+        let layout = layout_game_window(window_rect(120, 40), &HEX_DUMP_PANE).unwrap();
+        assert_eq!(layout.history.left, 100);
+        assert_eq!(layout.history.bottom(), 36);
 
-        let end_selection = move_and_refit(
-            start_selection,
-            movement,
-            &words,
-            &word_offsets,
-            &hex_dump_pane_dimensions,
-            2,
-        );
+        let (min_width, min_height) = min_game_window_size(&HEX_DUMP_PANE);
+        assert_eq!((min_width, min_height), (72, 21));
+        assert!(layout_game_window(window_rect(min_width, min_height), &HEX_DUMP_PANE).is_some());
+        assert!(layout_game_window(window_rect(min_width - 1, 24), &HEX_DUMP_PANE).is_none());
+        assert!(layout_game_window(window_rect(80, min_height - 1), &HEX_DUMP_PANE).is_none());
 
-        assert_eq!(end_selection, expected_end_selection);
+        // smaller panes still leave room for the header and the history
+        let small_pane = HexDumpPane::new(6, 6);
+        assert_eq!(min_game_window_size(&small_pane), (66, 17));
+        let layout = layout_game_window(window_rect(66, 17), &small_pane).unwrap();
+        assert_eq!(layout.hex_dump_rects[1].right(), 36);
+        assert_eq!(layout.history.height, 8);
     }
 
     #[test]
-    fn test_word_move_wrap_vertical() {
-        //        v-start
-        // .... ....
-        // .abc .xyz
-        // .... ....
-        //        ^-end
-        let start_selection = SelectedChunk {
-            pane_num: 1,
-            row_num: 0,
-            col_start: 2,
-            len: 1,
-        };
-        let expected_end_selection = SelectedChunk {
-            pane_num: 1,
-            row_num: 2,
-            col_start: 2,
-            len: 1,
-        };
-        let movement = Movement::Up;
-        let words = ["abc", "xyz"];
-        let word_offsets = [5, 17];
-        let hex_dump_pane_dimensions = HexDumpPane {
-            dump_width: 4,
-            dump_height: 3,
-            addr_width: 0,           // unused
-            addr_to_dump_padding: 0, // unused
-        };
-
-        let end_selection = move_and_refit(
-            start_selection,
-            movement,
-            &words,
-            &word_offsets,
-            &hex_dump_pane_dimensions,
-            2,
+    fn test_hex_dump_chunk_at() {
+        let window_rect = Rect {
+            left: 0,
+            top: 0,
+            width: 80,
+            height: 24,
+        };
+        let layout = layout_game_window(window_rect, &HEX_DUMP_PANE).unwrap();
+        let chunk_at = |y, x| layout.hex_dump_chunk_at(&HEX_DUMP_PANE, y, x);
+
+        // each pane's dump starts after its address and padding
+        assert_eq!(
+            chunk_at(5, 10),
+            Some(SelectedChunk {
+                pane_num: 0,
+                row_num: 0,
+                col_start: 0,
+                len: 1,
+            })
         );
-
-        assert_eq!(end_selection, expected_end_selection);
-    }
-
-    #[test]
-    fn test_word_move_right() {
-        // v  v
-        // abc. ....
-        // .... .xyz
-        // .... ....
-        let start_selection = SelectedChunk {
-            pane_num: 0,
-            row_num: 0,
-            col_start: 0,
-            len: 3,
-        };
-        let expected_end_selection = SelectedChunk {
-            pane_num: 0,
-            row_num: 0,
-            col_start: 3,
-            len: 1,
-        };
-        let movement = Movement::Right;
-        let words = ["abc", "xyz"];
-        let word_offsets = [0, 17];
-        let hex_dump_pane_dimensions = HexDumpPane {
-            dump_width: 4,
-            dump_height: 3,
-            addr_width: 0,           // unused
-            addr_to_dump_padding: 0, // unused
-        };
-
-        let end_selection = move_and_refit(
-            start_selection,
-            movement,
-            &words,
-            &word_offsets,
-            &hex_dump_pane_dimensions,
-            2,
+        assert_eq!(
+            chunk_at(20, 47),
+            Some(SelectedChunk {
+                pane_num: 1,
+                row_num: 15,
+                col_start: 11,
+                len: 1,
+            })
         );
 
-        assert_eq!(end_selection, expected_end_selection);
+        // addresses, the gap between the panes and the header don't point at anything
+        assert_eq!(chunk_at(5, 9), None);
+        assert_eq!(chunk_at(5, 22), None);
+        assert_eq!(chunk_at(5, 35), None);
+        assert_eq!(chunk_at(4, 10), None);
+        assert_eq!(chunk_at(21, 10), None);
+    }
+
+    fn test_board() -> Board {
+        let mut hex_dump = ".".repeat(HEX_DUMP_PANE.max_bytes_in_pane() * 2);
+        hex_dump.replace_range(2..6, "dude");
+        hex_dump.replace_range(27..31, "rude");
+        hex_dump.replace_range(194..199, "<#$%>");
+        Board {
+            pane_size: PaneSize {
+                width: 12,
+                height: 16,
+            },
+            hex_dump,
+            words: vec![String::from("dude"), String::from("rude")],
+            word_offsets: vec![2, 27],
+            solution: String::from("dude"),
+            bracket_sequences: Vec::new(),
+            start_addr: 0xF000,
+        }
     }
 
-    #[test]
-    fn test_word_move_left() {
-        // abc. ....
-        // .... .xyz
-        // .... ^^..
-        let start_selection = SelectedChunk {
-            pane_num: 1,
-            row_num: 1,
-            col_start: 1,
-            len: 3,
-        };
-        let expected_end_selection = SelectedChunk {
-            pane_num: 1,
-            row_num: 1,
-            col_start: 0,
-            len: 1,
-        };
-        let movement = Movement::Left;
-        let words = ["abc", "xyz"];
-        let word_offsets = [0, 17];
-        let hex_dump_pane_dimensions = HexDumpPane {
-            dump_width: 4,
-            dump_height: 3,
-            addr_width: 0,           // unused
-            addr_to_dump_padding: 0, // unused
-        };
-
-        let end_selection = move_and_refit(
-            start_selection,
-            movement,
-            &words,
-            &word_offsets,
-            &hex_dump_pane_dimensions,
-            2,
-        );
+    // Hands the game a fixed list of inputs, one per frame
+    struct ScriptedInputSource(std::collections::VecDeque<GameInput>);
 
-        assert_eq!(end_selection, expected_end_selection);
+    impl InputSource for ScriptedInputSource {
+        fn poll_input(&mut self) -> Option<GameInput> {
+            self.0.pop_front()
+        }
     }
 
     #[test]
-    fn test_move_word_wrapped() {
-        //   v  v
-        // ..ab ....
-        // c... .xyz
-        // .... ....
-        let start_selection = SelectedChunk {
-            pane_num: 0,
-            row_num: 0,
-            col_start: 2,
-            len: 3,
-        };
-        let expected_end_selection = SelectedChunk {
-            pane_num: 1,
-            row_num: 0,
-            col_start: 0,
-            len: 1,
-        };
-        let movement = Movement::Right;
-        let words = ["abc", "xyz"];
-        let word_offsets = [2, 17];
-        let hex_dump_pane_dimensions = HexDumpPane {
-            dump_width: 4,
-            dump_height: 3,
-            addr_width: 0,           // unused
-            addr_to_dump_padding: 0, // unused
-        };
-
-        let end_selection = move_and_refit(
-            start_selection,
-            movement,
-            &words,
-            &word_offsets,
-            &hex_dump_pane_dimensions,
-            2,
+    fn test_recording_keeps_where_the_mouse_put_the_cursor() {
+        let mouse = |action, y, x| GameInput::Player(InputCmd::Mouse(MouseEvent { action, y, x }));
+        let mut input_source = ScriptedInputSource(
+            vec![
+                mouse(MouseAction::Hover, 5, 13),
+                mouse(MouseAction::Hover, 7, 14),
+                GameInput::Player(InputCmd::Move(Movement::Right)),
+                mouse(MouseAction::Hover, 20, 47),
+                mouse(MouseAction::Click, 5, 12),
+                GameInput::Player(InputCmd::Quit),
+            ]
+            .into(),
         );
-
-        assert_eq!(end_selection, expected_end_selection);
-    }
-
-    #[test]
-    fn test_move_up_into_word_selection_vertical() {
-        //        v-start
-        // .... ....
-        // .abc ....
-        // .... .xyz
-        //       ^-end
-        let start_selection = SelectedChunk {
-            pane_num: 1,
-            row_num: 0,
-            col_start: 2,
-            len: 1,
-        };
-        let expected_end_selection = SelectedChunk {
-            pane_num: 1,
-            row_num: 2,
-            col_start: 1,
-            len: 3,
-        };
-        let movement = Movement::Up;
-        let words = ["abc", "xyz"];
-        let word_offsets = [5, 21];
-        let hex_dump_pane_dimensions = HexDumpPane {
-            dump_width: 4,
-            dump_height: 3,
-            addr_width: 0,           // unused
-            addr_to_dump_padding: 0, // unused
-        };
-
-        let end_selection = move_and_refit(
-            start_selection,
-            movement,
-            &words,
-            &word_offsets,
-            &hex_dump_pane_dimensions,
-            2,
+        let game_result = run_game(
+            &DifficultyProfile::preset(Difficulty::Average),
+            Some(test_board()),
+            Some(1),
+            None,
+            &DictSource::Builtin,
+            &mut input_source,
+            &HeadlessTerminal::new(80, 24),
+        )
+        .unwrap();
+        assert_eq!(game_result.outcome, GameOutcome::Won);
+
+        // hovering isn't recorded until something depends on where it left the cursor, and then
+        // it's where the cursor ended up rather than where the mouse was on the screen
+        let recorded_cmds: Vec<GameCmd> = game_result
+            .recording
+            .inputs
+            .iter()
+            .map(|(_, game_cmd)| *game_cmd)
+            .collect();
+        assert_eq!(
+            recorded_cmds,
+            vec![
+                GameCmd::MoveTo {
+                    pane_num: 0,
+                    row_num: 2,
+                    col: 3,
+                },
+                GameCmd::Move(Movement::Right),
+                GameCmd::MoveTo {
+                    pane_num: 0,
+                    row_num: 0,
+                    col: 2,
+                },
+                GameCmd::Select,
+                GameCmd::Quit,
+            ]
         );
-
-        assert_eq!(end_selection, expected_end_selection);
     }
 
     #[test]
-    fn test_move_down_into_word_selection_vertical() {
-        // .... ....
-        // .abc ..v-start
-        // .... .xyz
-        //       ^-end
-        let start_selection = SelectedChunk {
-            pane_num: 1,
-            row_num: 1,
-            col_start: 2,
-            len: 1,
-        };
-        let expected_end_selection = SelectedChunk {
-            pane_num: 1,
-            row_num: 2,
-            col_start: 1,
-            len: 3,
-        };
-        let movement = Movement::Down;
-        let words = ["abc", "xyz"];
-        let word_offsets = [5, 21];
-        let hex_dump_pane_dimensions = HexDumpPane {
-            dump_width: 4,
-            dump_height: 3,
-            addr_width: 0,           // unused
-            addr_to_dump_padding: 0, // unused
-        };
-
-        let end_selection = move_and_refit(
-            start_selection,
-            movement,
-            &words,
-            &word_offsets,
-            &hex_dump_pane_dimensions,
-            2,
-        );
-
-        assert_eq!(end_selection, expected_end_selection);
+    fn test_recording_keeps_generated_boards() {
+        let mut input_source = ScriptedInputSource(vec![GameInput::Player(InputCmd::Quit)].into());
+        let game_result = run_game(
+            &DifficultyProfile::preset(Difficulty::Easy),
+            None,
+            Some(1),
+            None,
+            &DictSource::Builtin,
+            &mut input_source,
+            &HeadlessTerminal::new(80, 24),
+        )
+        .unwrap();
+
+        // replays play the same board whatever dictionary they're given
+        let recording = Recording::from_text(&game_result.recording.to_text()).unwrap();
+        assert_eq!(recording.board, Some(game_result.board));
     }
 
     #[test]
-    fn test_move_left_into_cross_pane_word_selection() {
-        //       v-start
-        // .... z...
-        // .abc ....
-        // ..xy ....
-        //   ^-end
-        let start_selection = SelectedChunk {
-            pane_num: 1,
-            row_num: 0,
-            col_start: 1,
-            len: 1,
-        };
-        let expected_end_selection = SelectedChunk {
-            pane_num: 0,
-            row_num: 2,
-            col_start: 2,
-            len: 3,
-        };
-        let movement = Movement::Left;
-        let words = ["abc", "xyz"];
-        let word_offsets = [5, 10];
-        let hex_dump_pane_dimensions = HexDumpPane {
-            dump_width: 4,
-            dump_height: 3,
-            addr_width: 0,           // unused
-            addr_to_dump_padding: 0, // unused
-        };
-
-        let end_selection = move_and_refit(
-            start_selection,
-            movement,
-            &words,
-            &word_offsets,
-            &hex_dump_pane_dimensions,
-            2,
+    fn test_render_game_window_snapshot() {
+        let board = test_board();
+        let window_rect = Rect {
+            left: 0,
+            top: 0,
+            width: 72,
+            height: 21,
+        };
+        let layout = layout_game_window(window_rect, &HEX_DUMP_PANE).unwrap();
+
+        // guess rude and then leave the cursor on dude
+        let mut game_state = GameState::new(
+            board,
+            MAX_ATTEMPTS,
+            Some(std::time::Duration::from_secs(90)),
+            Box::new(SeededRangeRng::new(0)),
         );
-
-        assert_eq!(end_selection, expected_end_selection);
-    }
-
-    #[test]
-    fn ensure_word_len_for_difficulty_matches_hamming_distance_distribution_for_difficulty() {
-        let difficulties = [
-            Difficulty::VeryEasy,
-            Difficulty::Easy,
-            Difficulty::Average,
-            Difficulty::Hard,
-            Difficulty::VeryHard,
+        let game_cmds = [
+            GameCmd::Tick(std::time::Duration::from_millis(15_800)),
+            GameCmd::MoveTo {
+                pane_num: 0,
+                row_num: 2,
+                col: 3,
+            },
+            GameCmd::Select,
+            GameCmd::MoveTo {
+                pane_num: 0,
+                row_num: 0,
+                col: 4,
+            },
         ];
+        for game_cmd in &game_cmds {
+            game_state.handle(*game_cmd);
+        }
 
-        for d in &difficulties {
-            let word_len = get_word_len_for_difficulty(*d);
-            let hamming_distance_distribution = get_hamming_distance_distribution(*d);
+        let terminal = HeadlessTerminal::new(72, 21);
+        render_game_window(
+            &terminal,
+            &game_state,
+            &HEX_DUMP_PANE,
+            &layout,
+            42,
+            &[
+                HistoryEntry::Denied(String::from("rude"), 3),
+                HistoryEntry::DudRemoved(String::from("<#$%>")),
+            ],
+        );
 
-            for hdd_entry in &hamming_distance_distribution {
-                assert!(hdd_entry.hamming_distance <= word_len);
-            }
-        }
+        let expected_text = r#"ROBCO INDUSTRIES (TM) TERMALINK PROTOCOL                         SEED 42
+ENTER PASSWORD NOW
+
+# ATTEMPT(S) LEFT: # # #      TIME LEFT: 1:15
+
+0xF000    ..dude......    0xF180    ..<#$%>.....
+0xF00C    ............    0xF18C    ............
+0xF018    ...rude.....    0xF198    ............
+0xF024    ............    0xF1A4    ............
+0xF030    ............    0xF1B0    ............
+0xF03C    ............    0xF1BC    ............
+0xF048    ............    0xF1C8    ............
+0xF054    ............    0xF1D4    ............    >Entry denied
+0xF060    ............    0xF1E0    ............    >3/4 correct.
+0xF06C    ............    0xF1EC    ............    >rude
+0xF078    ............    0xF1F8    ............    ><#$%>
+0xF084    ............    0xF204    ............    >Dud removed.
+0xF090    ............    0xF210    ............
+0xF09C    ............    0xF21C    ............
+0xF0A8    ............    0xF228    ............
+0xF0B4    ............    0xF234    ............
+"#;
+        assert_eq!(terminal.text(), expected_text);
+        assert!((2..6).all(|x| terminal.cell(5, 10 + x).attrs == Attrs::BLINK));
+        assert_eq!(terminal.cell(5, 10 + 6).attrs, Attrs::default());
     }
 }
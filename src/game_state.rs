@@ -0,0 +1,1143 @@
+//! The rules of a game, kept apart from any front end. A front end turns the player's input into
+//! commands, hands them to the game state along with the clock ticking and gets back events
+//! describing what happened. It then draws the state however it likes.
+
+use crate::board::{Board, BracketSequence, PaneSize, NUM_PANES};
+use crate::utils::str_utils::matching_char_count_ignore_case;
+use snm_rand_utils::range_rng::RangeRng;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Movement {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+// TODO: this chunk selection logic is pretty ugly. Can it be refactored for readability?
+/// The chunk of the hex dump under the cursor. Words and bracket sequences are selected whole.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelectedChunk {
+    pub pane_num: usize,
+    pub row_num: usize,
+    pub col_start: usize,
+    pub len: usize,
+}
+
+impl SelectedChunk {
+    /// Where the chunk starts in the hex dump
+    pub fn offset(&self, pane_size: &PaneSize) -> usize {
+        self.pane_num * pane_size.bytes() + self.row_num * pane_size.width + self.col_start
+    }
+
+    /// The single char at an offset into the hex dump
+    pub fn at_offset(offset: usize, pane_size: &PaneSize) -> Self {
+        SelectedChunk {
+            pane_num: offset / pane_size.bytes(),
+            row_num: (offset % pane_size.bytes()) / pane_size.width,
+            col_start: offset % pane_size.width,
+            len: 1,
+        }
+    }
+}
+
+// What selecting a chunk of the hex dump picked out
+#[derive(Debug, PartialEq, Eq)]
+enum Selection {
+    Word(usize), // index into the selectable words
+    Brackets(BracketSequence),
+}
+
+fn move_selection(
+    selection: SelectedChunk,
+    movement: Movement,
+    pane_size: &PaneSize,
+    num_panes: usize,
+) -> SelectedChunk {
+    let (col_move, row_move): (i32, i32) = match movement {
+        Movement::Down => (0, 1),
+        Movement::Up => (0, -1),
+        Movement::Left => (-1, 0),
+        // We might be at the beginning of a full word, in which case move past the end of the word
+        Movement::Right => (selection.len as i32, 0),
+    };
+
+    // Naively update the row and col with our movement
+    let mut next_col = col_move + (selection.col_start as i32);
+    let mut next_row = row_move + (selection.row_num as i32);
+    let mut next_pane = selection.pane_num as i32;
+
+    // Check if we've moved from one pane to another by moving laterally across the columns in a row.
+    if next_col >= pane_size.width as i32 {
+        next_col = 0;
+        next_pane += 1;
+    } else if next_col < 0 {
+        next_col = pane_size.width as i32 - 1;
+        next_pane -= 1;
+    }
+
+    // Check if we've moved to an invalid row outside of our pane.
+    // In which case just wrap around to the next valid row in the same pane.
+    if next_row >= pane_size.height as i32 {
+        next_row = 0;
+    } else if next_row < 0 {
+        next_row = pane_size.height as i32 - 1;
+    }
+
+    // Check if we've moved to an invalid pane outside of our hex dump.
+    // In which case just wrap around to the next valid pane.
+    if next_pane >= num_panes as i32 {
+        next_pane = 0;
+    } else if next_pane < 0 {
+        next_pane = 1;
+    }
+
+    SelectedChunk {
+        pane_num: next_pane as usize,
+        row_num: next_row as usize,
+        col_start: next_col as usize,
+        len: 1,
+    }
+}
+
+fn refit_selection<S: AsRef<str>>(
+    selection: SelectedChunk,
+    words: &[S],
+    word_offsets: &[usize],
+    bracket_sequences: &[BracketSequence],
+    pane_size: &PaneSize,
+) -> SelectedChunk {
+    let cursor_index = selection.offset(pane_size);
+    let mut result_selection = selection;
+
+    // turn our list of words and word_offsets into a list of ranges where those words live
+    // in the contiguous hex dump memory span
+    let word_ranges = words
+        .iter()
+        .zip(word_offsets.iter())
+        .map(|(word, word_offset)| (*word_offset, word_offset + word.as_ref().len()));
+
+    for word_range in word_ranges {
+        if cursor_index >= word_range.0 && cursor_index < word_range.1 {
+            // if our cursor is on or in the middle of a full word, update the cursor selection
+            // to highlight the whole word from wherever it starts, which may be rows or a pane back
+            let mut word_selection = SelectedChunk::at_offset(word_range.0, pane_size);
+            word_selection.len = word_range.1 - word_range.0;
+            return word_selection;
+        }
+    }
+
+    // bracket sequences only get highlighted from their opening bracket and never span rows
+    for bracket_sequence in bracket_sequences {
+        if cursor_index == bracket_sequence.offset {
+            result_selection.len = bracket_sequence.len;
+            break;
+        }
+    }
+
+    result_selection
+}
+
+fn try_select<S: AsRef<str>>(
+    selection: &SelectedChunk,
+    words: &[S],
+    word_offsets: &[usize],
+    bracket_sequences: &[BracketSequence],
+    pane_size: &PaneSize,
+) -> Option<Selection> {
+    let cursor_index = selection.offset(pane_size);
+
+    for (word_index, (word, word_offset)) in words.iter().zip(word_offsets.iter()).enumerate() {
+        if cursor_index >= *word_offset && cursor_index < word_offset + word.as_ref().len() {
+            // For safety we'll return the word if the cursor is anywhere in the word selection,
+            // but we only really expect it to be at the start of the word.
+            assert_eq!(cursor_index, *word_offset);
+            assert_eq!(selection.len, word.as_ref().len());
+            return Some(Selection::Word(word_index));
+        }
+    }
+
+    bracket_sequences
+        .iter()
+        .find(|b| cursor_index == b.offset && selection.len == b.len)
+        .map(|b| Selection::Brackets(*b))
+}
+
+/// Something the player did, or the clock ticking
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameCmd {
+    Move(Movement),
+    /// Put the cursor on a char of the hex dump, e.g. the one the mouse is pointing at
+    MoveTo {
+        pane_num: usize,
+        row_num: usize,
+        col: usize,
+    },
+    Select,
+    Quit,
+    /// The time since the game started
+    Tick(Duration),
+}
+
+/// What happened as a result of a command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameEvent {
+    EntryDenied {
+        word: String,
+        likeness: usize,
+    },
+    AccessGranted(String),
+    /// The bracket sequence which removed the dud
+    DudRemoved(String),
+    /// The bracket sequence which reset the attempts
+    TriesReset(String),
+    /// Every attempt has been used up
+    LockedOut,
+    TimedOut,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameOutcome {
+    Won,
+    Lost,
+    TimedOut,
+    Quit,
+}
+
+const TRIES_RESET_ODDS: usize = 5; // 1 in 5 bracket sequences resets the tries
+
+/// A game being played on a board
+pub struct GameState {
+    board: Board,
+    rng: Box<dyn RangeRng<usize>>,
+    selection: SelectedChunk,
+    attempts: usize,
+    attempts_used: usize,
+    time_limit: Option<Duration>,
+    elapsed: Duration,
+    guesses: Vec<String>,
+    outcome: Option<GameOutcome>,
+    // the clock stops once the game is over
+    duration: Option<Duration>,
+}
+
+impl GameState {
+    /// Start a game on a freshly generated board. The rng decides what each bracket sequence does
+    /// so carrying on with the rng the board was generated from keeps the whole game seeded.
+    pub fn new(
+        board: Board,
+        attempts: usize,
+        time_limit: Option<Duration>,
+        rng: Box<dyn RangeRng<usize>>,
+    ) -> Self {
+        // initially select the first character, refit in case it's part of a larger word
+        let first_char = SelectedChunk {
+            pane_num: 0,
+            row_num: 0,
+            col_start: 0,
+            len: 1,
+        };
+        let selection = refit_selection(
+            first_char,
+            &board.words,
+            &board.word_offsets,
+            &board.bracket_sequences,
+            &board.pane_size,
+        );
+
+        GameState {
+            board,
+            rng,
+            selection,
+            attempts,
+            attempts_used: 0,
+            time_limit,
+            elapsed: Duration::from_secs(0),
+            guesses: Vec::new(),
+            outcome: None,
+            duration: None,
+        }
+    }
+
+    /// The board as it stands. Removed duds are blanked out of the hex dump and dropped from the
+    /// words, and used bracket sequences are dropped too.
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn selection(&self) -> &SelectedChunk {
+        &self.selection
+    }
+
+    pub fn attempts(&self) -> usize {
+        self.attempts
+    }
+
+    pub fn attempts_used(&self) -> usize {
+        self.attempts_used
+    }
+
+    /// Every word selected, in order
+    pub fn guesses(&self) -> &[String] {
+        &self.guesses
+    }
+
+    /// None until the game is over
+    pub fn outcome(&self) -> Option<GameOutcome> {
+        self.outcome
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.outcome.is_some()
+    }
+
+    /// How long the game has been going, or how long it went on for once it's over
+    pub fn duration(&self) -> Duration {
+        self.duration.unwrap_or(self.elapsed)
+    }
+
+    /// None for untimed games
+    pub fn time_left(&self) -> Option<Duration> {
+        self.time_limit
+            .map(|time_limit| time_limit.checked_sub(self.duration()).unwrap_or_default())
+    }
+
+    pub fn handle(&mut self, cmd: GameCmd) -> Vec<GameEvent> {
+        match cmd {
+            GameCmd::Move(movement) => {
+                let moved =
+                    move_selection(self.selection, movement, &self.board.pane_size, NUM_PANES);
+                self.refit(moved);
+                Vec::new()
+            }
+            GameCmd::MoveTo {
+                pane_num,
+                row_num,
+                col,
+            } => {
+                let pane_size = self.board.pane_size;
+                if pane_num < NUM_PANES && row_num < pane_size.height && col < pane_size.width {
+                    self.refit(SelectedChunk {
+                        pane_num,
+                        row_num,
+                        col_start: col,
+                        len: 1,
+                    });
+                }
+                Vec::new()
+            }
+            GameCmd::Select => self.select(),
+            GameCmd::Quit => {
+                if !self.is_over() {
+                    self.end(GameOutcome::Quit);
+                }
+                Vec::new()
+            }
+            GameCmd::Tick(elapsed) => {
+                self.elapsed = elapsed;
+
+                // Lock the terminal as soon as time runs out, before any late input gets handled
+                match self.time_limit {
+                    Some(time_limit) if elapsed >= time_limit && !self.is_over() => {
+                        self.outcome = Some(GameOutcome::TimedOut);
+                        self.duration = Some(time_limit);
+                        vec![GameEvent::TimedOut]
+                    }
+                    _ => Vec::new(),
+                }
+            }
+        }
+    }
+
+    // If the cursor is now on a word or bracket sequence, refit the selection to cover all of it
+    fn refit(&mut self, selection: SelectedChunk) {
+        self.selection = refit_selection(
+            selection,
+            &self.board.words,
+            &self.board.word_offsets,
+            &self.board.bracket_sequences,
+            &self.board.pane_size,
+        );
+    }
+
+    fn end(&mut self, outcome: GameOutcome) {
+        self.outcome = Some(outcome);
+        self.duration = Some(self.elapsed);
+    }
+
+    fn select(&mut self) -> Vec<GameEvent> {
+        if self.is_over() {
+            return Vec::new();
+        }
+
+        let selection = try_select(
+            &self.selection,
+            &self.board.words,
+            &self.board.word_offsets,
+            &self.board.bracket_sequences,
+            &self.board.pane_size,
+        );
+        match selection {
+            Some(Selection::Word(word_index)) => {
+                let word = self.board.words[word_index].clone();
+                self.guesses.push(word.clone());
+                if word == self.board.solution {
+                    self.end(GameOutcome::Won);
+                    return vec![GameEvent::AccessGranted(word)];
+                }
+
+                let likeness = matching_char_count_ignore_case(&self.board.solution, &word);
+                self.attempts_used += 1;
+                let mut events = vec![GameEvent::EntryDenied { word, likeness }];
+                if self.attempts_used == self.attempts {
+                    self.end(GameOutcome::Lost);
+                    events.push(GameEvent::LockedOut);
+                }
+                events
+            }
+            Some(Selection::Brackets(brackets)) => {
+                let board = &mut self.board;
+                let brackets_text =
+                    String::from(&board.hex_dump[brackets.offset..][..brackets.len]);
+                let dud_indices: Vec<usize> = (0..board.words.len())
+                    .filter(|i| board.words[*i] != board.solution)
+                    .collect();
+
+                let event =
+                    if dud_indices.is_empty() || self.rng.gen_range(0, TRIES_RESET_ODDS) == 0 {
+                        self.attempts_used = 0;
+                        GameEvent::TriesReset(brackets_text)
+                    } else {
+                        // blank out the dud in the hex dump so it can't be selected again
+                        let dud_index = dud_indices[self.rng.gen_range(0, dud_indices.len())];
+                        let dud_offset = board.word_offsets[dud_index];
+                        let dud_len = board.words[dud_index].len();
+                        board
+                            .hex_dump
+                            .replace_range(dud_offset..dud_offset + dud_len, &".".repeat(dud_len));
+                        board.words.remove(dud_index);
+                        board.word_offsets.remove(dud_index);
+                        GameEvent::DudRemoved(brackets_text)
+                    };
+
+                // each bracket sequence can only be used once
+                board.bracket_sequences.retain(|b| *b != brackets);
+                self.selection.len = 1;
+                vec![event]
+            }
+            None => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use snm_rand_utils::mocks as rand_mocks;
+
+    // .... abd.
+    // .abc .xyz
+    // (..) ....
+    pub(crate) fn test_board() -> Board {
+        Board {
+            pane_size: PaneSize {
+                width: 4,
+                height: 3,
+            },
+            hex_dump: String::from(".....abc(..)abd..xyz...."),
+            words: vec![
+                String::from("abc"),
+                String::from("abd"),
+                String::from("xyz"),
+            ],
+            word_offsets: vec![5, 12, 17],
+            solution: String::from("abc"),
+            bracket_sequences: vec![BracketSequence { offset: 8, len: 4 }],
+            start_addr: 0xF000,
+        }
+    }
+
+    fn test_game(attempts: usize, time_limit: Option<Duration>, rng_value: usize) -> GameState {
+        let rng = rand_mocks::SingleValueRangeRng::new(rng_value);
+        GameState::new(test_board(), attempts, time_limit, Box::new(rng))
+    }
+
+    fn select_at(game_state: &mut GameState, pane_num: usize, row_num: usize) -> Vec<GameEvent> {
+        let col = if (pane_num, row_num) == (1, 0) { 0 } else { 1 };
+        game_state.handle(GameCmd::MoveTo {
+            pane_num,
+            row_num,
+            col,
+        });
+        game_state.handle(GameCmd::Select)
+    }
+
+    #[test]
+    fn check_entry_denied_until_locked_out() {
+        let mut game_state = test_game(2, None, 0);
+        assert_eq!(
+            select_at(&mut game_state, 1, 0),
+            vec![GameEvent::EntryDenied {
+                word: String::from("abd"),
+                likeness: 2,
+            }]
+        );
+        assert_eq!(game_state.attempts_used(), 1);
+        assert!(!game_state.is_over());
+
+        assert_eq!(
+            select_at(&mut game_state, 1, 1),
+            vec![
+                GameEvent::EntryDenied {
+                    word: String::from("xyz"),
+                    likeness: 0,
+                },
+                GameEvent::LockedOut,
+            ]
+        );
+        assert_eq!(game_state.outcome(), Some(GameOutcome::Lost));
+
+        // nothing can be selected once the terminal is locked
+        assert_eq!(select_at(&mut game_state, 0, 1), vec![]);
+        assert_eq!(game_state.guesses(), ["abd", "xyz"]);
+    }
+
+    #[test]
+    fn check_access_granted() {
+        let mut game_state = test_game(4, None, 0);
+        game_state.handle(GameCmd::Tick(Duration::from_secs(10)));
+        assert_eq!(
+            select_at(&mut game_state, 0, 1),
+            vec![GameEvent::AccessGranted(String::from("abc"))]
+        );
+        assert_eq!(game_state.outcome(), Some(GameOutcome::Won));
+
+        // the clock stops once the game is over
+        game_state.handle(GameCmd::Tick(Duration::from_secs(20)));
+        assert_eq!(game_state.duration(), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn check_bracket_sequences() {
+        // a zero from the rng always resets the tries
+        let mut game_state = test_game(4, None, 0);
+        select_at(&mut game_state, 1, 0);
+        game_state.handle(GameCmd::MoveTo {
+            pane_num: 0,
+            row_num: 2,
+            col: 0,
+        });
+        assert_eq!(game_state.selection().len, 4);
+        assert_eq!(
+            game_state.handle(GameCmd::Select),
+            vec![GameEvent::TriesReset(String::from("(..)"))]
+        );
+        assert_eq!(game_state.attempts_used(), 0);
+
+        // each bracket sequence only works once
+        assert_eq!(game_state.selection().len, 1);
+        assert_eq!(game_state.handle(GameCmd::Select), vec![]);
+        assert!(game_state.board().bracket_sequences.is_empty());
+
+        // anything else removes a dud
+        let mut game_state = test_game(4, None, 1);
+        game_state.handle(GameCmd::MoveTo {
+            pane_num: 0,
+            row_num: 2,
+            col: 0,
+        });
+        assert_eq!(
+            game_state.handle(GameCmd::Select),
+            vec![GameEvent::DudRemoved(String::from("(..)"))]
+        );
+        assert_eq!(game_state.board().words, ["abc", "abd"]);
+        assert_eq!(game_state.board().hex_dump, ".....abc(..)abd.........");
+        assert_eq!(select_at(&mut game_state, 1, 1), vec![]);
+    }
+
+    #[test]
+    fn check_time_limit() {
+        let mut game_state = test_game(4, Some(Duration::from_secs(30)), 0);
+        assert_eq!(
+            game_state.handle(GameCmd::Tick(Duration::from_secs(29))),
+            vec![]
+        );
+        assert_eq!(game_state.time_left(), Some(Duration::from_secs(1)));
+        assert_eq!(
+            game_state.handle(GameCmd::Tick(Duration::from_secs(31))),
+            vec![GameEvent::TimedOut]
+        );
+        assert_eq!(game_state.outcome(), Some(GameOutcome::TimedOut));
+        assert_eq!(game_state.time_left(), Some(Duration::from_secs(0)));
+        assert_eq!(game_state.duration(), Duration::from_secs(30));
+        assert_eq!(select_at(&mut game_state, 0, 1), vec![]);
+
+        // untimed games never run out of time
+        let mut game_state = test_game(4, None, 0);
+        game_state.handle(GameCmd::Tick(Duration::from_secs(60 * 60)));
+        assert!(!game_state.is_over());
+        assert_eq!(game_state.time_left(), None);
+    }
+
+    #[test]
+    fn check_quit_and_move_to() {
+        let mut game_state = test_game(4, None, 0);
+
+        // pointing into the middle of a word selects all of it and pointing off the board does
+        // nothing
+        game_state.handle(GameCmd::MoveTo {
+            pane_num: 1,
+            row_num: 1,
+            col: 3,
+        });
+        let on_xyz = SelectedChunk {
+            pane_num: 1,
+            row_num: 1,
+            col_start: 1,
+            len: 3,
+        };
+        assert_eq!(*game_state.selection(), on_xyz);
+        game_state.handle(GameCmd::MoveTo {
+            pane_num: 2,
+            row_num: 0,
+            col: 0,
+        });
+        assert_eq!(*game_state.selection(), on_xyz);
+
+        game_state.handle(GameCmd::Quit);
+        assert_eq!(game_state.outcome(), Some(GameOutcome::Quit));
+    }
+
+    #[test]
+    fn test_refit_and_select_bracket_sequence() {
+        // .... ....
+        // .abc ....
+        // (..) .xyz
+        // ^
+        let words = ["abc", "xyz"];
+        let word_offsets = [5, 21];
+        let bracket_sequences = [BracketSequence { offset: 8, len: 4 }];
+        let pane_size = PaneSize {
+            width: 4,
+            height: 3,
+        };
+
+        let on_open_bracket = SelectedChunk {
+            pane_num: 0,
+            row_num: 2,
+            col_start: 0,
+            len: 1,
+        };
+        let refit = refit_selection(
+            on_open_bracket,
+            &words,
+            &word_offsets,
+            &bracket_sequences,
+            &pane_size,
+        );
+        assert_eq!(refit.len, 4);
+        assert_eq!(
+            try_select(
+                &refit,
+                &words,
+                &word_offsets,
+                &bracket_sequences,
+                &pane_size
+            ),
+            Some(Selection::Brackets(bracket_sequences[0]))
+        );
+
+        // the middle of a bracket sequence is just noise
+        let inside_brackets = SelectedChunk {
+            pane_num: 0,
+            row_num: 2,
+            col_start: 1,
+            len: 1,
+        };
+        let refit = refit_selection(
+            inside_brackets,
+            &words,
+            &word_offsets,
+            &bracket_sequences,
+            &pane_size,
+        );
+        assert_eq!(refit.len, 1);
+        assert_eq!(
+            try_select(
+                &refit,
+                &words,
+                &word_offsets,
+                &bracket_sequences,
+                &pane_size
+            ),
+            None
+        );
+
+        // words still select as words
+        let on_word = SelectedChunk {
+            pane_num: 1,
+            row_num: 2,
+            col_start: 1,
+            len: 3,
+        };
+        assert_eq!(
+            try_select(
+                &on_word,
+                &words,
+                &word_offsets,
+                &bracket_sequences,
+                &pane_size
+            ),
+            Some(Selection::Word(1))
+        );
+    }
+
+    #[test]
+    fn test_refit_word_over_three_rows() {
+        // .... ..ab
+        // .... cdef
+        // .... ghij
+        //        ^
+        let words = ["abcdefghij"];
+        let word_offsets = [14];
+        let pane_size = PaneSize {
+            width: 4,
+            height: 3,
+        };
+
+        let on_word_end = SelectedChunk {
+            pane_num: 1,
+            row_num: 2,
+            col_start: 3,
+            len: 1,
+        };
+        let refit = refit_selection(on_word_end, &words, &word_offsets, &[], &pane_size);
+        assert_eq!(
+            refit,
+            SelectedChunk {
+                pane_num: 1,
+                row_num: 0,
+                col_start: 2,
+                len: 10,
+            }
+        );
+        assert_eq!(
+            try_select(&refit, &words, &word_offsets, &[], &pane_size),
+            Some(Selection::Word(0))
+        );
+
+        // a word can run on from the end of the first pane into the second
+        let word_offsets = [10];
+        let in_second_pane = SelectedChunk {
+            pane_num: 1,
+            row_num: 1,
+            col_start: 0,
+            len: 1,
+        };
+        let refit = refit_selection(in_second_pane, &words, &word_offsets, &[], &pane_size);
+        assert_eq!(
+            refit,
+            SelectedChunk {
+                pane_num: 0,
+                row_num: 2,
+                col_start: 2,
+                len: 10,
+            }
+        );
+    }
+
+    fn move_and_refit(
+        mut selection: SelectedChunk,
+        movement: Movement,
+        words: &[&str],
+        word_offsets: &[usize],
+        pane_size: &PaneSize,
+        num_panes: usize,
+    ) -> SelectedChunk {
+        selection = move_selection(selection, movement, pane_size, num_panes);
+        refit_selection(selection, words, word_offsets, &[], pane_size)
+    }
+
+    #[test]
+    fn test_single_char_move_next() {
+        // .... ....
+        // .abc .xyz
+        // .... ....
+        //  ^^
+        let start_selection = SelectedChunk {
+            pane_num: 0,
+            row_num: 2,
+            col_start: 1,
+            len: 1,
+        };
+        let expected_end_selection = SelectedChunk {
+            pane_num: 0,
+            row_num: 2,
+            col_start: 2,
+            len: 1,
+        };
+        let movement = Movement::Right;
+        let words = ["abc", "xyz"];
+        let word_offsets = [5, 17];
+        let pane_size = PaneSize {
+            width: 4,
+            height: 3,
+        };
+
+        let end_selection = move_and_refit(
+            start_selection,
+            movement,
+            &words,
+            &word_offsets,
+            &pane_size,
+            2,
+        );
+
+        assert_eq!(end_selection, expected_end_selection);
+    }
+
+    #[test]
+    fn test_single_char_move_across_panes_right() {
+        // .... ....
+        // .abc .xyz
+        // .... ....
+        //    ^ ^
+        let start_selection = SelectedChunk {
+            pane_num: 0,
+            row_num: 2,
+            col_start: 3,
+            len: 1,
+        };
+        let expected_end_selection = SelectedChunk {
+            pane_num: 1,
+            row_num: 2,
+            col_start: 0,
+            len: 1,
+        };
+        let movement = Movement::Right;
+        let words = ["abc", "xyz"];
+        let word_offsets = [5, 17];
+        let pane_size = PaneSize {
+            width: 4,
+            height: 3,
+        };
+
+        let end_selection = move_and_refit(
+            start_selection,
+            movement,
+            &words,
+            &word_offsets,
+            &pane_size,
+            2,
+        );
+
+        assert_eq!(end_selection, expected_end_selection);
+    }
+
+    #[test]
+    fn test_single_char_move_across_panes_left() {
+        // .... ....
+        // .abc .xyz
+        // .... ....
+        // ^       ^
+        let start_selection = SelectedChunk {
+            pane_num: 0,
+            row_num: 2,
+            col_start: 0,
+            len: 1,
+        };
+        let expected_end_selection = SelectedChunk {
+            pane_num: 1,
+            row_num: 2,
+            col_start: 3,
+            len: 1,
+        };
+        let movement = Movement::Left;
+        let words = ["abc", "xyz"];
+        let word_offsets = [5, 17];
+        let pane_size = PaneSize {
+            width: 4,
+            height: 3,
+        };
+
+        let end_selection = move_and_refit(
+            start_selection,
+            movement,
+            &words,
+            &word_offsets,
+            &pane_size,
+            2,
+        );
+
+        assert_eq!(end_selection, expected_end_selection);
+    }
+
+    #[test]
+    fn test_word_move_wrap_vertical() {
+        //        v-start
+        // .... ....
+        // .abc .xyz
+        // .... ....
+        //        ^-end
+        let start_selection = SelectedChunk {
+            pane_num: 1,
+            row_num: 0,
+            col_start: 2,
+            len: 1,
+        };
+        let expected_end_selection = SelectedChunk {
+            pane_num: 1,
+            row_num: 2,
+            col_start: 2,
+            len: 1,
+        };
+        let movement = Movement::Up;
+        let words = ["abc", "xyz"];
+        let word_offsets = [5, 17];
+        let pane_size = PaneSize {
+            width: 4,
+            height: 3,
+        };
+
+        let end_selection = move_and_refit(
+            start_selection,
+            movement,
+            &words,
+            &word_offsets,
+            &pane_size,
+            2,
+        );
+
+        assert_eq!(end_selection, expected_end_selection);
+    }
+
+    #[test]
+    fn test_word_move_right() {
+        // v  v
+        // abc. ....
+        // .... .xyz
+        // .... ....
+        let start_selection = SelectedChunk {
+            pane_num: 0,
+            row_num: 0,
+            col_start: 0,
+            len: 3,
+        };
+        let expected_end_selection = SelectedChunk {
+            pane_num: 0,
+            row_num: 0,
+            col_start: 3,
+            len: 1,
+        };
+        let movement = Movement::Right;
+        let words = ["abc", "xyz"];
+        let word_offsets = [0, 17];
+        let pane_size = PaneSize {
+            width: 4,
+            height: 3,
+        };
+
+        let end_selection = move_and_refit(
+            start_selection,
+            movement,
+            &words,
+            &word_offsets,
+            &pane_size,
+            2,
+        );
+
+        assert_eq!(end_selection, expected_end_selection);
+    }
+
+    #[test]
+    fn test_word_move_left() {
+        // abc. ....
+        // .... .xyz
+        // .... ^^..
+        let start_selection = SelectedChunk {
+            pane_num: 1,
+            row_num: 1,
+            col_start: 1,
+            len: 3,
+        };
+        let expected_end_selection = SelectedChunk {
+            pane_num: 1,
+            row_num: 1,
+            col_start: 0,
+            len: 1,
+        };
+        let movement = Movement::Left;
+        let words = ["abc", "xyz"];
+        let word_offsets = [0, 17];
+        let pane_size = PaneSize {
+            width: 4,
+            height: 3,
+        };
+
+        let end_selection = move_and_refit(
+            start_selection,
+            movement,
+            &words,
+            &word_offsets,
+            &pane_size,
+            2,
+        );
+
+        assert_eq!(end_selection, expected_end_selection);
+    }
+
+    #[test]
+    fn test_move_word_wrapped() {
+        //   v  v
+        // ..ab ....
+        // c... .xyz
+        // .... ....
+        let start_selection = SelectedChunk {
+            pane_num: 0,
+            row_num: 0,
+            col_start: 2,
+            len: 3,
+        };
+        let expected_end_selection = SelectedChunk {
+            pane_num: 1,
+            row_num: 0,
+            col_start: 0,
+            len: 1,
+        };
+        let movement = Movement::Right;
+        let words = ["abc", "xyz"];
+        let word_offsets = [2, 17];
+        let pane_size = PaneSize {
+            width: 4,
+            height: 3,
+        };
+
+        let end_selection = move_and_refit(
+            start_selection,
+            movement,
+            &words,
+            &word_offsets,
+            &pane_size,
+            2,
+        );
+
+        assert_eq!(end_selection, expected_end_selection);
+    }
+
+    #[test]
+    fn test_move_up_into_word_selection_vertical() {
+        //        v-start
+        // .... ....
+        // .abc ....
+        // .... .xyz
+        //       ^-end
+        let start_selection = SelectedChunk {
+            pane_num: 1,
+            row_num: 0,
+            col_start: 2,
+            len: 1,
+        };
+        let expected_end_selection = SelectedChunk {
+            pane_num: 1,
+            row_num: 2,
+            col_start: 1,
+            len: 3,
+        };
+        let movement = Movement::Up;
+        let words = ["abc", "xyz"];
+        let word_offsets = [5, 21];
+        let pane_size = PaneSize {
+            width: 4,
+            height: 3,
+        };
+
+        let end_selection = move_and_refit(
+            start_selection,
+            movement,
+            &words,
+            &word_offsets,
+            &pane_size,
+            2,
+        );
+
+        assert_eq!(end_selection, expected_end_selection);
+    }
+
+    #[test]
+    fn test_move_down_into_word_selection_vertical() {
+        // .... ....
+        // .abc ..v-start
+        // .... .xyz
+        //       ^-end
+        let start_selection = SelectedChunk {
+            pane_num: 1,
+            row_num: 1,
+            col_start: 2,
+            len: 1,
+        };
+        let expected_end_selection = SelectedChunk {
+            pane_num: 1,
+            row_num: 2,
+            col_start: 1,
+            len: 3,
+        };
+        let movement = Movement::Down;
+        let words = ["abc", "xyz"];
+        let word_offsets = [5, 21];
+        let pane_size = PaneSize {
+            width: 4,
+            height: 3,
+        };
+
+        let end_selection = move_and_refit(
+            start_selection,
+            movement,
+            &words,
+            &word_offsets,
+            &pane_size,
+            2,
+        );
+
+        assert_eq!(end_selection, expected_end_selection);
+    }
+
+    #[test]
+    fn test_move_left_into_cross_pane_word_selection() {
+        //       v-start
+        // .... z...
+        // .abc ....
+        // ..xy ....
+        //   ^-end
+        let start_selection = SelectedChunk {
+            pane_num: 1,
+            row_num: 0,
+            col_start: 1,
+            len: 1,
+        };
+        let expected_end_selection = SelectedChunk {
+            pane_num: 0,
+            row_num: 2,
+            col_start: 2,
+            len: 3,
+        };
+        let movement = Movement::Left;
+        let words = ["abc", "xyz"];
+        let word_offsets = [5, 10];
+        let pane_size = PaneSize {
+            width: 4,
+            height: 3,
+        };
+
+        let end_selection = move_and_refit(
+            start_selection,
+            movement,
+            &words,
+            &word_offsets,
+            &pane_size,
+            2,
+        );
+
+        assert_eq!(end_selection, expected_end_selection);
+    }
+}
@@ -0,0 +1,271 @@
+use crate::app_dirs;
+use crate::tui::{Key, MouseAction, MouseEvent};
+use fonv_cracker::game_state::Movement;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+// What a key press asks for, whichever key it was. Screens which take typed text (i.e. the solver)
+// check whether a key is being typed before looking up its command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputCmd {
+    Move(Movement),
+    Select,
+    Quit,
+    // The mouse can't be rebound, each screen works out what it's pointing at
+    Mouse(MouseEvent),
+}
+
+pub fn input_cmd_name(input_cmd: InputCmd) -> &'static str {
+    match input_cmd {
+        InputCmd::Move(Movement::Up) => "up",
+        InputCmd::Move(Movement::Down) => "down",
+        InputCmd::Move(Movement::Left) => "left",
+        InputCmd::Move(Movement::Right) => "right",
+        InputCmd::Select => "select",
+        InputCmd::Quit => "quit",
+        InputCmd::Mouse(event) => match event.action {
+            MouseAction::Hover => "hover",
+            MouseAction::Click => "click",
+        },
+    }
+}
+
+pub fn parse_input_cmd(name: &str) -> Option<InputCmd> {
+    match name {
+        "up" => Some(InputCmd::Move(Movement::Up)),
+        "down" => Some(InputCmd::Move(Movement::Down)),
+        "left" => Some(InputCmd::Move(Movement::Left)),
+        "right" => Some(InputCmd::Move(Movement::Right)),
+        "select" => Some(InputCmd::Select),
+        "quit" => Some(InputCmd::Quit),
+        _ => None,
+    }
+}
+
+// Single characters stand for themselves, everything else goes by name
+fn parse_key(name: &str) -> Option<Key> {
+    let mut chars = name.chars();
+    if let (Some(ch), None) = (chars.next(), chars.next()) {
+        return Some(Key::Char(ch));
+    }
+
+    match name {
+        "up" => Some(Key::Up),
+        "down" => Some(Key::Down),
+        "left" => Some(Key::Left),
+        "right" => Some(Key::Right),
+        "enter" => Some(Key::Enter),
+        "esc" => Some(Key::Esc),
+        "tab" => Some(Key::Tab),
+        "space" => Some(Key::Char(' ')),
+        "backspace" => Some(Key::Backspace),
+        "delete" => Some(Key::Delete),
+        "center" => Some(Key::KeypadCenter),
+        _ => None,
+    }
+}
+
+const KEY_BINDINGS_FILE_NAME: &str = "keys.txt";
+
+#[derive(Debug)]
+pub enum KeyBindingsErr {
+    ReadFile(PathBuf, std::io::Error),
+    InvalidLine(usize, String),
+}
+
+impl std::fmt::Display for KeyBindingsErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            KeyBindingsErr::ReadFile(path, err) => {
+                write!(f, "couldn't read key bindings {}: {}", path.display(), err)
+            }
+            KeyBindingsErr::InvalidLine(line_num, line) => {
+                write!(f, "invalid key bindings line {}: '{}'", line_num, line)
+            }
+        }
+    }
+}
+
+// Which command each key triggers. A command can have any number of keys but each key only ever
+// triggers one command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBindings {
+    bindings: HashMap<Key, InputCmd>,
+}
+
+impl Default for KeyBindings {
+    // Arrow keys, WASD, vim keys and the numpad with num lock either on or off
+    fn default() -> Self {
+        let up = InputCmd::Move(Movement::Up);
+        let down = InputCmd::Move(Movement::Down);
+        let left = InputCmd::Move(Movement::Left);
+        let right = InputCmd::Move(Movement::Right);
+        let default_bindings = [
+            (
+                up,
+                [Key::Up, Key::Char('w'), Key::Char('k'), Key::Char('8')],
+            ),
+            (
+                down,
+                [Key::Down, Key::Char('s'), Key::Char('j'), Key::Char('2')],
+            ),
+            (
+                left,
+                [Key::Left, Key::Char('a'), Key::Char('h'), Key::Char('4')],
+            ),
+            (
+                right,
+                [Key::Right, Key::Char('d'), Key::Char('l'), Key::Char('6')],
+            ),
+            (
+                InputCmd::Select,
+                [
+                    Key::Enter,
+                    Key::Char(' '),
+                    Key::Char('5'),
+                    Key::KeypadCenter,
+                ],
+            ),
+        ];
+
+        let mut key_bindings = KeyBindings {
+            bindings: HashMap::new(),
+        };
+        for (input_cmd, keys) in &default_bindings {
+            key_bindings.rebind(*input_cmd, keys);
+        }
+        key_bindings.rebind(InputCmd::Quit, &[Key::Esc]);
+        key_bindings
+    }
+}
+
+impl KeyBindings {
+    pub fn input_cmd(&self, key: Key) -> Option<InputCmd> {
+        match key {
+            Key::Mouse(event) => Some(InputCmd::Mouse(event)),
+            _ => self.bindings.get(&key).copied(),
+        }
+    }
+
+    // Replace the keys for a command. The keys are taken from whichever commands had them before.
+    pub fn rebind(&mut self, input_cmd: InputCmd, keys: &[Key]) {
+        self.bindings.retain(|_, bound_cmd| *bound_cmd != input_cmd);
+        for key in keys {
+            self.bindings.insert(*key, input_cmd);
+        }
+    }
+
+    // Each line is a command followed by the keys which trigger it, e.g. "select enter space".
+    // Commands which aren't listed keep their default keys.
+    pub fn from_text(text: &str) -> Result<Self, KeyBindingsErr> {
+        let mut key_bindings = KeyBindings::default();
+        for (i, line) in text.lines().map(|line| line.trim()).enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let line_num = i + 1;
+            let invalid_line = || KeyBindingsErr::InvalidLine(line_num, String::from(line));
+
+            let mut fields = line.split_whitespace();
+            let input_cmd = fields
+                .next()
+                .and_then(parse_input_cmd)
+                .ok_or_else(invalid_line)?;
+            let keys = fields
+                .map(parse_key)
+                .collect::<Option<Vec<Key>>>()
+                .filter(|keys| !keys.is_empty())
+                .ok_or_else(invalid_line)?;
+            key_bindings.rebind(input_cmd, &keys);
+        }
+
+        Ok(key_bindings)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, KeyBindingsErr> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| KeyBindingsErr::ReadFile(path.to_path_buf(), e))?;
+        Self::from_text(&text)
+    }
+
+    // Load the bindings from the user's config dir, sticking with the defaults if there aren't any
+    pub fn load_from_config_dir() -> Result<Self, KeyBindingsErr> {
+        let path = match app_dirs::config_dir() {
+            Some(config_dir) => config_dir.join(KEY_BINDINGS_FILE_NAME),
+            None => return Ok(KeyBindings::default()),
+        };
+
+        match Self::load(&path) {
+            Err(KeyBindingsErr::ReadFile(_, e)) if e.kind() == std::io::ErrorKind::NotFound => {
+                Ok(KeyBindings::default())
+            }
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_default_key_bindings() {
+        let key_bindings = KeyBindings::default();
+        for key in &[Key::Up, Key::Char('w'), Key::Char('k'), Key::Char('8')] {
+            assert_eq!(
+                key_bindings.input_cmd(*key),
+                Some(InputCmd::Move(Movement::Up))
+            );
+        }
+        assert_eq!(
+            key_bindings.input_cmd(Key::KeypadCenter),
+            Some(InputCmd::Select)
+        );
+        assert_eq!(key_bindings.input_cmd(Key::Esc), Some(InputCmd::Quit));
+        assert_eq!(key_bindings.input_cmd(Key::Char('x')), None);
+    }
+
+    #[test]
+    fn check_key_bindings_from_text() {
+        let key_bindings =
+            KeyBindings::from_text("# swap select over to q\n\nselect q enter\nquit x esc\n")
+                .unwrap();
+        assert_eq!(
+            key_bindings.input_cmd(Key::Char('q')),
+            Some(InputCmd::Select)
+        );
+        assert_eq!(key_bindings.input_cmd(Key::Enter), Some(InputCmd::Select));
+        assert_eq!(key_bindings.input_cmd(Key::Char('x')), Some(InputCmd::Quit));
+        // listing a command replaces its default keys but leaves the other commands alone
+        assert_eq!(key_bindings.input_cmd(Key::Char(' ')), None);
+        assert_eq!(
+            key_bindings.input_cmd(Key::Char('w')),
+            Some(InputCmd::Move(Movement::Up))
+        );
+
+        // a key can be moved over from another command
+        let key_bindings = KeyBindings::from_text("select w").unwrap();
+        assert_eq!(
+            key_bindings.input_cmd(Key::Char('w')),
+            Some(InputCmd::Select)
+        );
+        assert_eq!(
+            key_bindings.input_cmd(Key::Up),
+            Some(InputCmd::Move(Movement::Up))
+        );
+
+        assert!(matches!(
+            KeyBindings::from_text("jump space"),
+            Err(KeyBindingsErr::InvalidLine(1, _))
+        ));
+        assert!(matches!(
+            KeyBindings::from_text("up w\nselect"),
+            Err(KeyBindingsErr::InvalidLine(2, _))
+        ));
+        assert!(matches!(
+            KeyBindings::from_text("up pageup"),
+            Err(KeyBindingsErr::InvalidLine(1, _))
+        ));
+    }
+}
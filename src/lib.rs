@@ -0,0 +1,31 @@
+//! Word generation and password solving for the Fallout: New Vegas terminal hacking minigame.
+//!
+//! - [`dict`] loads the English word lists the passwords come from
+//! - [`board`] generates the words for a game, hides them in a hex dump and saves boards to files
+//! - [`game_state`] plays a game on a board, following the rules without drawing anything
+//! - [`profile`] describes what makes one game harder than another
+//! - [`solver`] narrows a password list down from the likeness of each guess
+//! - [`decision_tree`] works out a guessing strategy which needs the fewest attempts
+//! - [`self_play`] plays games with a guessing strategy to benchmark how hard each difficulty is
+//!
+//! The most used items are re-exported from the crate root.
+
+extern crate snm_rand_utils;
+#[macro_use]
+extern crate static_assertions;
+
+pub mod board;
+pub mod decision_tree;
+pub mod dict;
+pub mod game_state;
+pub mod profile;
+pub mod self_play;
+pub mod solver;
+pub mod utils;
+
+pub use board::{generate_words, obfuscate_words, Board, BoardErr, Difficulty, GenerateErr};
+pub use dict::dict::{DictLoadErr, DictSource, EnglishDictChunk, HammingDistanceIterator};
+pub use game_state::{GameCmd, GameEvent, GameState};
+pub use profile::DifficultyProfile;
+pub use solver::{filter_matching_passwords, KnownGuess};
+pub use utils::str_utils::{hamming_dist_ignore_case, matching_char_count_ignore_case};
@@ -1,66 +1,284 @@
 extern crate pancurses;
 extern crate snm_rand_utils;
-extern crate snm_simple_file;
-#[macro_use]
-extern crate static_assertions;
 
-mod dict;
+// The curses front end. Generating boards and solving passwords lives in the fonv_cracker library.
+mod app_dirs;
 mod game;
-mod solver;
-mod utils;
+mod input;
+mod recording;
+mod solver_ui;
+mod stats;
+mod tui;
 
-use utils::tui::ascii_keycodes;
-use utils::tui::pancurses as pancurses_utils;
-use utils::tui::Rect;
+use fonv_cracker::dict::dict::DictSource;
+use fonv_cracker::game_state::Movement;
+use fonv_cracker::profile::{DifficultyProfile, ProfileErr, MAX_WORD_COUNT_WITH_SOLVABILITY};
+use fonv_cracker::{board, decision_tree, dict, self_play, solver};
+use input::{InputCmd, KeyBindings};
+use std::io::Write;
+use tui::pancurses as pancurses_utils;
+use tui::{Attrs, MouseAction, Rect, Terminal};
+
+// The difficulty profile is only put together from these once the args are parsed so a bad
+// profile file gets reported like any other bad input file
+#[derive(Debug)]
+struct GameArgs {
+    difficulty: board::Difficulty,
+    profile_file: Option<std::path::PathBuf>,
+    profile_settings: Vec<String>,
+    seed: Option<u64>,
+    timed: bool,
+    time_limit: Option<std::time::Duration>,
+    record_file: Option<String>,
+    board_file: Option<std::path::PathBuf>,
+    export_board_file: Option<std::path::PathBuf>,
+}
+
+#[derive(Debug)]
+struct BenchmarkArgs {
+    strategy_names: Vec<&'static str>,
+    profile_settings: Vec<String>,
+    games: usize,
+    seed: u64,
+}
 
 #[derive(Debug)]
 enum Mode {
     LaunchGui,
-    LaunchGame(game::Difficulty),
-    LaunchSolver(String, Vec<String>),
+    LaunchGame(GameArgs),
+    ReplayGame(String),
+    LaunchSolver(Option<String>, Vec<String>),
+    RunHeadlessSolver(String, Vec<String>),
+    PrintDecisionTree(String, decision_tree::OutputFormat),
+    RunBenchmark(BenchmarkArgs),
 }
 
 #[derive(Debug)]
 struct CmdlineArgs {
     mode: Mode,
+    dict_source: DictSource,
+    key_bindings_file: Option<std::path::PathBuf>,
 }
 
 fn parse_cmdline_args() -> Result<CmdlineArgs, &'static str> {
-    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+
+    // Options for every mode come before the mode. The dictionary options override the dictionary
+    // environment variables.
+    let mut dict_source = None;
+    let mut key_bindings_file = None;
+    while let Some(option_arg) = args.first() {
+        let is_option = ["--dict-dir", "--dict-file", "--keys"].contains(&option_arg.as_str());
+        if !is_option {
+            break;
+        }
+
+        let path = match args.get(1) {
+            Some(path_arg) => std::path::PathBuf::from(path_arg),
+            None => return Err("Missing path arg for option"),
+        };
+        match option_arg.as_str() {
+            "--dict-dir" => dict_source = Some(DictSource::Dir(path)),
+            "--dict-file" => dict_source = Some(DictSource::MixedLengthFile(path)),
+            _ => key_bindings_file = Some(path),
+        }
+        args.drain(..2);
+    }
+    let dict_source = dict_source.unwrap_or_else(DictSource::from_env);
+
     if args.is_empty() {
         return Ok(CmdlineArgs {
             mode: Mode::LaunchGui,
+            dict_source,
+            key_bindings_file,
         });
     }
 
     let mode_arg = &args[0];
     let mode = match mode_arg.as_str() {
         "--solver" => {
+            // without an input file the solver starts with an empty password list to type into
+            let known_guess_args = args.iter().skip(2).cloned().collect();
+            Mode::LaunchSolver(args.get(1).cloned(), known_guess_args)
+        }
+        "--headless-solver" => {
             if args.len() < 2 {
-                return Err("Missing input file arg for solver mode");
+                return Err("Missing input file arg for headless solver mode");
             }
 
-            let known_guess_args = args.iter().skip(2).map(|a| a.clone()).collect();
-            Mode::LaunchSolver(args[1].clone(), known_guess_args)
+            let known_guess_args = args.iter().skip(2).cloned().collect();
+            Mode::RunHeadlessSolver(args[1].clone(), known_guess_args)
+        }
+        "--decision-tree" => {
+            if args.len() < 2 {
+                return Err("Missing input file arg for decision tree mode");
+            }
+
+            let format = match args.get(2).map(|a| a.as_str()) {
+                None => decision_tree::OutputFormat::Text,
+                Some("--json") => decision_tree::OutputFormat::Json,
+                Some(_) => return Err("Invalid output format arg for decision tree mode"),
+            };
+            Mode::PrintDecisionTree(args[1].clone(), format)
         }
         "--game" => {
             if args.len() < 2 {
                 return Err("Missing difficulty arg for game mode");
             }
 
-            let parsed_difficulty = args[1].parse::<game::Difficulty>()?;
-            Mode::LaunchGame(parsed_difficulty)
+            let mut game_args = GameArgs {
+                difficulty: args[1].parse::<board::Difficulty>()?,
+                profile_file: None,
+                profile_settings: Vec::new(),
+                seed: None,
+                timed: false,
+                time_limit: None,
+                record_file: None,
+                board_file: None,
+                export_board_file: None,
+            };
+            let mut option_args = args.iter().skip(2);
+            while let Some(option_arg) = option_args.next() {
+                // --timed is a flag, every other option takes a value
+                if option_arg == "--timed" {
+                    game_args.timed = true;
+                    continue;
+                }
+
+                match (option_arg.as_str(), option_args.next()) {
+                    ("--seed", Some(seed_arg)) => match seed_arg.parse::<u64>() {
+                        Ok(parsed_seed) => game_args.seed = Some(parsed_seed),
+                        Err(_) => return Err("Invalid seed arg for game mode"),
+                    },
+                    ("--time-limit", Some(secs_arg)) => match secs_arg.parse::<u64>() {
+                        Ok(secs) if secs > 0 => {
+                            game_args.time_limit = Some(std::time::Duration::from_secs(secs))
+                        }
+                        _ => return Err("Invalid time limit arg for game mode"),
+                    },
+                    ("--record", Some(file_arg)) => game_args.record_file = Some(file_arg.clone()),
+                    ("--profile", Some(file_arg)) => {
+                        game_args.profile_file = Some(std::path::PathBuf::from(file_arg))
+                    }
+                    ("--board", Some(file_arg)) => {
+                        game_args.board_file = Some(std::path::PathBuf::from(file_arg))
+                    }
+                    ("--export-board", Some(file_arg)) => {
+                        game_args.export_board_file = Some(std::path::PathBuf::from(file_arg))
+                    }
+                    // e.g. --set attempts=6 or --set distances=1:2,4:9
+                    ("--set", Some(setting_arg)) => game_args
+                        .profile_settings
+                        .push(setting_arg.replacen('=', " ", 1).replace(',', " ")),
+                    ("--seed", None)
+                    | ("--time-limit", None)
+                    | ("--record", None)
+                    | ("--profile", None)
+                    | ("--board", None)
+                    | ("--export-board", None)
+                    | ("--set", None) => return Err("Missing value for game mode option"),
+                    _ => return Err("Invalid arg for game mode"),
+                }
+            }
+            Mode::LaunchGame(game_args)
+        }
+        "--benchmark" => {
+            let mut benchmark_args = BenchmarkArgs {
+                strategy_names: Vec::new(),
+                profile_settings: Vec::new(),
+                games: 1000,
+                seed: 0,
+            };
+            let mut option_args = args.iter().skip(1);
+            while let Some(option_arg) = option_args.next() {
+                match (option_arg.as_str(), option_args.next()) {
+                    ("--strategy", Some(name_arg)) => {
+                        match self_play::STRATEGY_NAMES.iter().find(|n| *n == name_arg) {
+                            Some(name) => benchmark_args.strategy_names.push(name),
+                            None => return Err("Invalid strategy arg for benchmark mode"),
+                        }
+                    }
+                    ("--games", Some(games_arg)) => match games_arg.parse::<usize>() {
+                        Ok(games) if games > 0 => benchmark_args.games = games,
+                        _ => return Err("Invalid games arg for benchmark mode"),
+                    },
+                    ("--seed", Some(seed_arg)) => match seed_arg.parse::<u64>() {
+                        Ok(seed) => benchmark_args.seed = seed,
+                        Err(_) => return Err("Invalid seed arg for benchmark mode"),
+                    },
+                    // applied to every difficulty's profile, e.g. --set min_solvability=4
+                    ("--set", Some(setting_arg)) => benchmark_args
+                        .profile_settings
+                        .push(setting_arg.replacen('=', " ", 1).replace(',', " ")),
+                    ("--strategy", None)
+                    | ("--games", None)
+                    | ("--seed", None)
+                    | ("--set", None) => return Err("Missing value for benchmark mode option"),
+                    _ => return Err("Invalid arg for benchmark mode"),
+                }
+            }
+            // every strategy gets compared unless some were picked
+            if benchmark_args.strategy_names.is_empty() {
+                benchmark_args.strategy_names = self_play::STRATEGY_NAMES.to_vec();
+            }
+            Mode::RunBenchmark(benchmark_args)
+        }
+        "--replay" => {
+            if args.len() < 2 {
+                return Err("Missing recording file arg for replay mode");
+            }
+
+            Mode::ReplayGame(args[1].clone())
         }
         _ => return Err("Invalid mode argument"),
     };
 
-    Ok(CmdlineArgs { mode })
+    Ok(CmdlineArgs {
+        mode,
+        dict_source,
+        key_bindings_file,
+    })
 }
 
 fn print_usage_and_exit(err_msg: &str) -> ! {
     println!("USAGE:");
-    println!("    fonv_cracker.exe --solver input_file [guess matching_char_count]+");
-    println!("    fonv_cracker.exe --game difficulty");
+    println!("    fonv_cracker.exe [options] --solver [input_file [guess matching_char_count]+]");
+    println!(
+        "    fonv_cracker.exe [options] --headless-solver input_file [guess matching_char_count]+"
+    );
+    println!("    fonv_cracker.exe [options] --decision-tree input_file [--json]");
+    println!("    fonv_cracker.exe [options] --game difficulty [--profile file] [--set setting=value]* [--seed n] [--timed | --time-limit secs] [--board file] [--export-board file] [--record file]");
+    println!("    fonv_cracker.exe [options] --replay file");
+    println!(
+        "    fonv_cracker.exe [options] --benchmark [--strategy name]* [--set setting=value]* [--games n] [--seed n]"
+    );
+    println!("OPTIONS:");
+    println!("    --dict-dir dir    directory of N_char_words_alpha.txt word lists");
+    println!("    --dict-file file  single word list of mixed length words");
+    println!(
+        "                      (or set {} or {})",
+        dict::dict::DICT_DIR_ENV_VAR,
+        dict::dict::DICT_FILE_ENV_VAR
+    );
+    println!("    --keys file       key bindings, one command per line followed by its keys");
+    println!("                      (defaults to keys.txt in the user's config dir)");
+    println!("PROFILE SETTINGS:");
+    println!("    word_len n        letters in each word");
+    println!("    words n           words in the hex dump, including the password");
+    println!("    attempts n        attempts before lockout, each wrong guess uses one");
+    println!("    distances d:n,..  n decoys d letters away from the password, closest first");
+    println!("    pane WxH          size of each hex dump pane");
+    println!("    brackets n        bracket sequences to hide in the hex dump");
+    println!("    time_limit secs   time given for --timed games");
+    println!("    min_solvability n cap: the best guesser always wins boards in <= n attempts");
+    println!("    max_solvability n floor: the best guesser can need >= n attempts on boards");
+    println!("    (profile files have one setting per line, e.g. 'pane 16x12')");
+    println!("BENCHMARK STRATEGIES:");
+    println!("    {}", self_play::STRATEGY_NAMES.join(", "));
+    println!("    (each plays --games boards per difficulty, all of them by default)");
+    println!("BOARD FILES:");
+    println!("    --export-board saves the game's board to play again with --board, which still");
+    println!("    takes the attempts and time limit from the difficulty profile");
     println!("Input err: {}", err_msg);
     std::process::exit(1);
 }
@@ -68,11 +286,13 @@ fn print_usage_and_exit(err_msg: &str) -> ! {
 #[derive(Debug, Clone, Copy)]
 enum Screen {
     StartMenu,
-    Game(game::Difficulty),
+    // the flag is whether the game is timed
+    Game(board::Difficulty, bool),
     Solver,
+    Stats,
 }
 
-fn run_start_menu(window: &pancurses::Window) -> Option<Screen> {
+fn run_start_menu(key_bindings: &KeyBindings, window: &dyn Terminal) -> Option<Screen> {
     const TITLE_LINES: [&str; 7] = [
         r#" _      __         __      __             __"#,
         r#"| | /| / /__ ____ / /____ / /__ ____  ___/ /"#,
@@ -83,107 +303,138 @@ fn run_start_menu(window: &pancurses::Window) -> Option<Screen> {
         r#"     \___/_/  \_,_/\__/_/\_\\__/_/"#,
     ];
 
-    let (window_height, window_width) = window.get_max_yx();
-
-    let title_rect = {
-        let title_width = TITLE_LINES.iter().map(|line| line.len()).max().unwrap() as i32;
-        const TITLE_HEIGHT: i32 = TITLE_LINES.len() as i32;
-
-        Rect {
-            // center the title horizontally
-            left: (window_width - title_width) / 2,
-            // place the title just above the horizontal divide
-            top: (window_height / 2) - (TITLE_HEIGHT + 1),
-            width: title_width,
-            height: TITLE_HEIGHT,
-        }
-    };
-
     let mut menu_cursor: usize = 0;
-    const MENU_OPTIONS: [&str; 5] = [
+    const MENU_OPTIONS: [&str; 7] = [
         "Start Game (easy)",
         "Start Game (average)",
         "Start Game (hard)",
+        "Start Timed Game (average)",
         "Launch Solver Utility",
+        "View Stats",
         "Quit",
     ];
 
     const MENU_OPTION_RESULTS: [Option<Screen>; MENU_OPTIONS.len()] = [
-        Some(Screen::Game(game::Difficulty::Easy)),
-        Some(Screen::Game(game::Difficulty::Average)),
-        Some(Screen::Game(game::Difficulty::Hard)),
+        Some(Screen::Game(board::Difficulty::Easy, false)),
+        Some(Screen::Game(board::Difficulty::Average, false)),
+        Some(Screen::Game(board::Difficulty::Hard, false)),
+        Some(Screen::Game(board::Difficulty::Average, true)),
         Some(Screen::Solver),
+        Some(Screen::Stats),
         None,
     ];
 
     let cursor_prefix = "> ";
-    let menu_rect = {
-        let max_menu_option_width = MENU_OPTIONS
-            .iter()
-            .map(|option_text| option_text.len())
-            .max()
-            .unwrap() as i32;
-        let menu_width = max_menu_option_width + cursor_prefix.len() as i32;
-        const MENU_HEIGHT: i32 = MENU_OPTIONS.len() as i32;
-
-        Rect {
+    let title_width = TITLE_LINES.iter().map(|line| line.len()).max().unwrap() as i32;
+    const TITLE_HEIGHT: i32 = TITLE_LINES.len() as i32;
+    let menu_width = MENU_OPTIONS
+        .iter()
+        .map(|option_text| option_text.len())
+        .max()
+        .unwrap() as i32
+        + cursor_prefix.len() as i32;
+    const MENU_HEIGHT: i32 = MENU_OPTIONS.len() as i32;
+
+    // the title and menu options each get half of the window with a row between them and the divide
+    let min_width = std::cmp::max(title_width, menu_width);
+    let min_height = 2 * (std::cmp::max(TITLE_HEIGHT, MENU_HEIGHT) + 1);
+    let layout_start_menu = |window_rect: Rect| {
+        if !window_rect.fits(min_width, min_height) {
+            return None;
+        }
+
+        let divide_row = window_rect.top + window_rect.height / 2;
+        let title_rect = Rect {
+            // center the title horizontally
+            left: window_rect.centered(title_width, TITLE_HEIGHT).left,
+            // place the title just above the horizontal divide
+            top: divide_row - (TITLE_HEIGHT + 1),
+            width: title_width,
+            height: TITLE_HEIGHT,
+        };
+        let menu_rect = Rect {
             // center the menu options horizontally
-            left: (window_width - menu_width) / 2,
+            left: window_rect.centered(menu_width, MENU_HEIGHT).left,
             // place the menu options just below the horizontal divide
-            top: (window_height / 2) + 1,
+            top: divide_row + 1,
             width: menu_width,
             height: MENU_HEIGHT,
-        }
+        };
+        Some((title_rect, menu_rect))
     };
 
+    let mut layout_window_rect = window.rect();
+    let mut layout = layout_start_menu(layout_window_rect);
+
     loop {
-        // clear the screen
-        window.erase();
-
-        // Render the title card
-        for (i, title_line) in TITLE_LINES.iter().enumerate() {
-            let row_offset = (i as i32) + title_rect.top;
-            let color_pair = pancurses_utils::green();
-            window.attron(color_pair);
-            window.mvaddstr(row_offset, title_rect.left, title_line);
-            window.attroff(color_pair);
-        }
-
-        // Render the menu options
-        for (i, menu_line) in MENU_OPTIONS.iter().enumerate() {
-            let row_offset = (i as i32) + menu_rect.top;
-            if i == menu_cursor {
-                window.mvaddstr(row_offset, menu_rect.left, cursor_prefix);
-            }
-            window.mvaddstr(
-                row_offset,
-                menu_rect.left + cursor_prefix.len() as i32,
-                menu_line,
-            );
+        // Lay the menu out again whenever the window has been resized
+        let window_rect = window.rect();
+        if window_rect != layout_window_rect {
+            layout_window_rect = window_rect;
+            layout = layout_start_menu(window_rect);
         }
 
-        // Input handling
-        // TODO: I think this input system might need some refactoring to share with the start menu
-        if let Some(pancurses::Input::Character(ch)) = window.getch() {
-            match ch {
-                // check for movement inputs
-                'w' => {
-                    menu_cursor = if menu_cursor == 0 {
-                        MENU_OPTIONS.len() - 1
-                    } else {
-                        menu_cursor - 1
+        match layout {
+            Some((title_rect, menu_rect)) => {
+                // clear the screen
+                window.erase();
+
+                // Render the title card
+                for (i, title_line) in TITLE_LINES.iter().enumerate() {
+                    let row_offset = (i as i32) + title_rect.top;
+                    window.attron(Attrs::GREEN);
+                    window.mvaddstr(row_offset, title_rect.left, title_line);
+                    window.attroff(Attrs::GREEN);
+                }
+
+                // Render the menu options
+                for (i, menu_line) in MENU_OPTIONS.iter().enumerate() {
+                    let row_offset = (i as i32) + menu_rect.top;
+                    if i == menu_cursor {
+                        window.mvaddstr(row_offset, menu_rect.left, cursor_prefix);
                     }
+                    window.mvaddstr(
+                        row_offset,
+                        menu_rect.left + cursor_prefix.len() as i32,
+                        menu_line,
+                    );
+                }
+            }
+            None => tui::render_too_small_message(window, min_width, min_height),
+        }
+
+        // Input handling
+        let input_cmd = window
+            .poll_key()
+            .and_then(|key| key_bindings.input_cmd(key));
+        match input_cmd {
+            Some(InputCmd::Move(Movement::Up)) => {
+                menu_cursor = if menu_cursor == 0 {
+                    MENU_OPTIONS.len() - 1
+                } else {
+                    menu_cursor - 1
                 }
-                's' => {
-                    menu_cursor = if menu_cursor == MENU_OPTIONS.len() - 1 {
-                        0
-                    } else {
-                        menu_cursor + 1
+            }
+            Some(InputCmd::Move(Movement::Down)) => {
+                menu_cursor = if menu_cursor == MENU_OPTIONS.len() - 1 {
+                    0
+                } else {
+                    menu_cursor + 1
+                }
+            }
+            Some(InputCmd::Select) => return MENU_OPTION_RESULTS[menu_cursor],
+            // Pointing at an option moves the cursor to it and clicking it picks it
+            Some(InputCmd::Mouse(event)) => {
+                if let Some((_, menu_rect)) = layout {
+                    if menu_rect.contains(event.y, event.x) {
+                        menu_cursor = (event.y - menu_rect.top) as usize;
+                        if event.action == MouseAction::Click {
+                            return MENU_OPTION_RESULTS[menu_cursor];
+                        }
                     }
                 }
-                ascii_keycodes::ENTER => return MENU_OPTION_RESULTS[menu_cursor],
-                _ => (),
             }
+            _ => (),
         };
 
         // blit the next frame
@@ -193,36 +444,438 @@ fn run_start_menu(window: &pancurses::Window) -> Option<Screen> {
 
 const TITLE: &str = "Wasteland Cracker";
 
+fn exit_with_error(err: &dyn std::fmt::Display) -> ! {
+    eprintln!("Error: {}", err);
+    std::process::exit(1);
+}
+
+// Exit codes for the headless solver so scripts can tell how close they are to the password
+mod headless_exit_codes {
+    pub const PASSWORD_FOUND: i32 = 0;
+    pub const INVALID_INPUT: i32 = 1;
+    pub const CANDIDATES_REMAIN: i32 = 2;
+    pub const NO_CANDIDATES: i32 = 3;
+}
+
+// Print the remaining candidates and the next guess to make without opening a window. Each line is
+// prefixed with what it describes so the output is easy to grep or parse.
+fn run_headless_solver(
+    password_file: &str,
+    guess_args: &[String],
+    dict_source: &DictSource,
+) -> i32 {
+    let candidate_passwords =
+        match solver::load_candidate_passwords(password_file, guess_args, dict_source) {
+            Ok(candidate_passwords) => candidate_passwords,
+            Err(e @ solver::SolverError::ContradictoryGuesses(_)) => {
+                eprintln!("{}", e);
+                return headless_exit_codes::NO_CANDIDATES;
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return headless_exit_codes::INVALID_INPUT;
+            }
+        };
+
+    if candidate_passwords.len() == 1 {
+        println!("password {}", candidate_passwords[0]);
+        return headless_exit_codes::PASSWORD_FOUND;
+    }
+
+    for candidate in &candidate_passwords {
+        println!("candidate {}", candidate);
+    }
+
+    match solver::recommend_guess(&candidate_passwords) {
+        Some((recommended_index, score)) => {
+            println!(
+                "guess {} ({} left at worst, {:.1} expected)",
+                candidate_passwords[recommended_index],
+                score.worst_case_remaining,
+                score.expected_remaining(candidate_passwords.len()),
+            );
+            headless_exit_codes::CANDIDATES_REMAIN
+        }
+        None => {
+            eprintln!("No password in the list matches every guess");
+            headless_exit_codes::NO_CANDIDATES
+        }
+    }
+}
+
+// Print a password list's decision tree without opening a window
+fn print_decision_tree(
+    password_file: &str,
+    dict_source: &DictSource,
+    format: decision_tree::OutputFormat,
+) -> Result<(), solver::SolverError> {
+    let input_passwords = solver::load_input_passwords(password_file, dict_source)?;
+
+    // validation guarantees a non-empty list so there is always some tree
+    let tree = decision_tree::build_decision_tree(&input_passwords).unwrap();
+    print!("{}", decision_tree::format_decision_tree(&tree, format));
+    Ok(())
+}
+
+fn describe_game_load_error(err: board::GenerateErr) -> String {
+    format!("Couldn't generate a board for the game: {}", err)
+}
+
+// Start from the difficulty's preset and apply the profile file, then any settings given on the
+// command line
+fn build_game_profile(game_args: &GameArgs) -> Result<DifficultyProfile, ProfileErr> {
+    let mut profile = DifficultyProfile::preset(game_args.difficulty);
+    if let Some(profile_file) = &game_args.profile_file {
+        profile.apply_file(profile_file)?;
+    }
+    for setting in &game_args.profile_settings {
+        profile.apply_setting(setting)?;
+    }
+    profile.validate()?;
+    Ok(profile)
+}
+
+// Every difficulty's preset with the same settings applied on top, labelled with the difficulty
+fn build_benchmark_profiles(
+    profile_settings: &[String],
+) -> Result<Vec<(board::Difficulty, DifficultyProfile)>, ProfileErr> {
+    let mut profiles = Vec::new();
+    for difficulty in board::ALL_DIFFICULTIES
+        .iter()
+        .filter(|d| **d != board::Difficulty::Custom)
+    {
+        let mut profile = DifficultyProfile::preset(*difficulty);
+        for setting in profile_settings {
+            profile.apply_setting(setting)?;
+        }
+        profile.validate()?;
+        profiles.push((*difficulty, profile));
+    }
+    Ok(profiles)
+}
+
+fn join_counts(counts: &[usize]) -> String {
+    let counts: Vec<String> = counts.iter().map(|count| count.to_string()).collect();
+    counts.join("/")
+}
+
+// Print a table of how each strategy did on each difficulty's profile, one difficulty at a time
+// as they finish. Each difficulty is followed by how many attempts its boards need in the worst
+// case.
+fn run_benchmark(
+    profiles: &[(board::Difficulty, DifficultyProfile)],
+    strategy_names: &[&str],
+    games: usize,
+    seed: u64,
+    dict_source: &DictSource,
+) -> Result<(), String> {
+    println!(
+        "{} game(s) per difficulty on the boards for seeds {} to {}",
+        games,
+        seed,
+        seed.wrapping_add(games.saturating_sub(1) as u64)
+    );
+    println!(
+        "{:<10} {:<17} {:>8}  {:<24} {:>6}",
+        "difficulty", "strategy", "win rate", "wins by guesses used", "lost"
+    );
+
+    for (difficulty, profile) in profiles {
+        // every difficulty gets fresh strategies so each one's results don't depend on the others
+        let mut strategies = Vec::new();
+        for name in strategy_names {
+            let strategy = self_play::new_strategy(name, seed)
+                .ok_or_else(|| format!("Unknown benchmark strategy '{}'", name))?;
+            strategies.push(strategy);
+        }
+        let (all_stats, solvability_stats) =
+            self_play::benchmark_profile(profile, &mut strategies, games, seed, dict_source)
+                .map_err(|e| e.to_string())?;
+        for (strategy, stats) in strategies.iter().zip(&all_stats) {
+            println!(
+                "{:<10} {:<17} {:>7.1}%  {:<24} {:>6}",
+                difficulty.to_string(),
+                strategy.name(),
+                stats.win_rate() * 100.0,
+                join_counts(&stats.wins_by_guesses),
+                stats.losses
+            );
+        }
+        match solvability_stats {
+            Some(solvability_stats) => println!(
+                "{:<10} boards by worst case attempts {}, {} can't always be won in {}",
+                difficulty.to_string(),
+                join_counts(&solvability_stats.boards_by_worst_case),
+                solvability_stats.unsolvable_within(profile.attempts),
+                profile.attempts
+            ),
+            None => println!(
+                "{:<10} worst case attempts aren't worked out for more than {} words",
+                difficulty.to_string(),
+                MAX_WORD_COUNT_WITH_SOLVABILITY
+            ),
+        }
+        std::io::stdout().flush().ok();
+    }
+    Ok(())
+}
+
+// Keep the game in the player's stats, and save its recording and board if they were asked for
+fn save_game_result(
+    game_result: &game::GameResult,
+    record_file: Option<&str>,
+    export_board_file: Option<&std::path::Path>,
+) -> Result<(), String> {
+    // each file is written whether or not the others could be so one failure doesn't lose the rest
+    let mut errors = Vec::new();
+    if let Err(e) = stats::record_game(game_result) {
+        errors.push(format!("Couldn't save stats: {}", e));
+    }
+    if let Some(record_file) = record_file {
+        if let Err(e) = game_result.recording.save(record_file) {
+            errors.push(e.to_string());
+        }
+    }
+    if let Some(export_board_file) = export_board_file {
+        if let Err(e) = game_result.board.save(export_board_file) {
+            errors.push(e.to_string());
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("\n"))
+    }
+}
+
 fn main() {
     let args = match parse_cmdline_args() {
         Ok(parsed_args) => parsed_args,
-        Err(err_msg) => print_usage_and_exit(&err_msg),
+        Err(err_msg) => print_usage_and_exit(err_msg),
     };
 
+    // Modes which only print their results don't need a window
+    match &args.mode {
+        Mode::RunHeadlessSolver(input_password_file, known_guess_args) => {
+            let exit_code =
+                run_headless_solver(input_password_file, known_guess_args, &args.dict_source);
+            std::process::exit(exit_code);
+        }
+        Mode::PrintDecisionTree(input_password_file, format) => {
+            if let Err(e) = print_decision_tree(input_password_file, &args.dict_source, *format) {
+                exit_with_error(&e);
+            }
+            return;
+        }
+        Mode::RunBenchmark(benchmark_args) => {
+            let profiles = match build_benchmark_profiles(&benchmark_args.profile_settings) {
+                Ok(profiles) => profiles,
+                Err(e) => exit_with_error(&e),
+            };
+            if let Err(e) = run_benchmark(
+                &profiles,
+                &benchmark_args.strategy_names,
+                benchmark_args.games,
+                benchmark_args.seed,
+                &args.dict_source,
+            ) {
+                exit_with_error(&e);
+            }
+            return;
+        }
+        _ => (),
+    }
+
+    // Load the solver input before setting up the window so bad input is reported on the cmdline
+    let (solver_passwords, solver_guesses) = match &args.mode {
+        Mode::LaunchSolver(Some(input_password_file), known_guess_args) => {
+            let input_passwords =
+                match solver::load_input_passwords(input_password_file, &args.dict_source) {
+                    Ok(input_passwords) => input_passwords,
+                    Err(e) => exit_with_error(&e),
+                };
+            match solver::parse_known_guesses(known_guess_args, &input_passwords) {
+                Ok(known_guesses) => (input_passwords, known_guesses),
+                Err(e) => exit_with_error(&e),
+            }
+        }
+        _ => (Vec::new(), Vec::new()),
+    };
+
+    // Likewise make sure the game's difficulty profile and board are playable
+    let game_profile = match &args.mode {
+        Mode::LaunchGame(game_args) => match build_game_profile(game_args) {
+            Ok(profile) => Some(profile),
+            Err(e) => exit_with_error(&e),
+        },
+        _ => None,
+    };
+    let game_board = match &args.mode {
+        Mode::LaunchGame(GameArgs {
+            board_file: Some(board_file),
+            ..
+        }) => match board::Board::load(board_file) {
+            Ok(board) => Some(board),
+            Err(e) => exit_with_error(&e),
+        },
+        _ => None,
+    };
+
+    // And that a recording can be played back
+    let replay = match &args.mode {
+        Mode::ReplayGame(recording_file) => match recording::Recording::load(recording_file) {
+            Ok(recording) => Some(recording),
+            Err(e) => exit_with_error(&e),
+        },
+        _ => None,
+    };
+
+    let key_bindings = match &args.key_bindings_file {
+        Some(path) => KeyBindings::load(path),
+        None => KeyBindings::load_from_config_dir(),
+    };
+    let key_bindings = key_bindings.unwrap_or_else(|e| exit_with_error(&e));
+
     let window = pancurses_utils::setup_window(TITLE);
-    match args.mode {
-        Mode::LaunchGame(difficulty) => game::run_game(difficulty, &window),
-        Mode::LaunchSolver(input_password_file, known_guess_args) => {
-            solver::solver(&input_password_file, &known_guess_args, &window)
+    let result = match args.mode {
+        Mode::LaunchGame(game_args) => {
+            let mut profile = game_profile.unwrap();
+            // a loaded board isn't what the difficulty would have generated so it counts as custom
+            if game_board.is_some() {
+                profile.difficulty = board::Difficulty::Custom;
+            }
+            let time_limit = match game_args.time_limit {
+                Some(time_limit) => Some(time_limit),
+                None if game_args.timed => Some(profile.time_limit),
+                None => None,
+            };
+            let mut input_source = game::WindowInputSource::new(&key_bindings, &window);
+            match game::run_game(
+                &profile,
+                game_board,
+                game_args.seed,
+                time_limit,
+                &args.dict_source,
+                &mut input_source,
+                &window,
+            ) {
+                Ok(game_result) => save_game_result(
+                    &game_result,
+                    game_args.record_file.as_deref(),
+                    game_args.export_board_file.as_deref(),
+                ),
+                Err(e) => Err(describe_game_load_error(e)),
+            }
         }
-        Mode::LaunchGui => run_full_gui(&window),
+        Mode::ReplayGame(..) => {
+            // replays were loaded before the window was set up
+            let recording = replay.unwrap();
+            let (profile, board, seed, time_limit) = (
+                recording.profile.clone(),
+                recording.board.clone(),
+                recording.seed,
+                recording.time_limit,
+            );
+            let mut input_source =
+                recording::ReplayInputSource::new(recording, &key_bindings, &window);
+            game::run_game(
+                &profile,
+                board,
+                Some(seed),
+                time_limit,
+                &args.dict_source,
+                &mut input_source,
+                &window,
+            )
+            .map(|_| ())
+            .map_err(describe_game_load_error)
+        }
+        Mode::LaunchSolver(..) => {
+            solver_ui::solver(
+                solver_passwords,
+                solver_guesses,
+                &args.dict_source,
+                &key_bindings,
+                &window,
+            );
+            Ok(())
+        }
+        Mode::LaunchGui => {
+            run_full_gui(&args.dict_source, &key_bindings, &window);
+            Ok(())
+        }
+        Mode::RunHeadlessSolver(..) | Mode::PrintDecisionTree(..) | Mode::RunBenchmark(..) => {
+            unreachable!()
+        }
+    };
+    pancurses_utils::teardown_window();
+
+    if let Err(err_msg) = result {
+        exit_with_error(&err_msg);
     }
-    pancurses::endwin();
 }
 
-fn run_full_gui(window: &pancurses::Window) {
+// Passwords the solver starts with when it's opened from the start menu, if the file exists
+const SOLVER_INPUT_FILE: &str = "assets/solver/input.txt";
+
+fn run_full_gui(dict_source: &DictSource, key_bindings: &KeyBindings, window: &dyn Terminal) {
     // Run the game until we quit
     let mut screen = Screen::StartMenu;
     loop {
         // Run the current screen until it signals a transition
         let next_screen = match screen {
-            Screen::StartMenu => run_start_menu(&window),
-            Screen::Game(difficulty) => {
-                game::run_game(difficulty, &window);
+            Screen::StartMenu => run_start_menu(key_bindings, window),
+            Screen::Game(difficulty, timed) => {
+                let profile = DifficultyProfile::preset(difficulty);
+                let time_limit = if timed {
+                    Some(profile.time_limit)
+                } else {
+                    None
+                };
+                let mut input_source = game::WindowInputSource::new(key_bindings, window);
+                let result = match game::run_game(
+                    &profile,
+                    None,
+                    None,
+                    time_limit,
+                    dict_source,
+                    &mut input_source,
+                    window,
+                ) {
+                    Ok(game_result) => save_game_result(&game_result, None, None),
+                    Err(e) => Err(describe_game_load_error(e)),
+                };
+                if let Err(err_msg) = result {
+                    tui::show_error_panel(window, &err_msg);
+                }
+                Some(Screen::StartMenu)
+            }
+            Screen::Stats => {
+                stats::run_stats_screen(window);
                 Some(Screen::StartMenu)
             }
             Screen::Solver => {
-                solver::solver("assets/solver/input.txt", &Vec::new(), &window);
+                // the example passwords are a starting point when they're around, otherwise the
+                // solver starts empty and they're typed in
+                let input_passwords = if std::path::Path::new(SOLVER_INPUT_FILE).exists() {
+                    match solver::load_input_passwords(SOLVER_INPUT_FILE, dict_source) {
+                        Ok(input_passwords) => input_passwords,
+                        Err(e) => {
+                            tui::show_error_panel(window, &e.to_string());
+                            Vec::new()
+                        }
+                    }
+                } else {
+                    Vec::new()
+                };
+                solver_ui::solver(
+                    input_passwords,
+                    Vec::new(),
+                    dict_source,
+                    key_bindings,
+                    window,
+                );
                 Some(Screen::StartMenu)
             }
         };
@@ -234,3 +887,95 @@ fn run_full_gui(window: &pancurses::Window) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tui::headless::HeadlessTerminal;
+    use tui::{Key, MouseEvent};
+
+    #[test]
+    fn check_start_menu_snapshot() {
+        let terminal = HeadlessTerminal::new(50, 18);
+        terminal.push_keys(&[Key::Down, Key::Char('j'), Key::Enter]);
+
+        let next_screen = run_start_menu(&KeyBindings::default(), &terminal);
+        assert!(matches!(
+            next_screen,
+            Some(Screen::Game(board::Difficulty::Hard, false))
+        ));
+        let expected_text = r#"
+    _      __         __      __             __
+   | | /| / /__ ____ / /____ / /__ ____  ___/ /
+   | |/ |/ / _ `(_-</ __/ -_) / _ `/ _ \/ _  /
+   |__/|__/\_,_/___/\__/\__/_/\_,_/_//_/\_,_/
+         / ___/______ _____/ /_____ ____
+        / /__/ __/ _ `/ __/  '_/ -_) __/
+        \___/_/  \_,_/\__/_/\_\\__/_/
+
+
+             Start Game (easy)
+             Start Game (average)
+           > Start Game (hard)
+             Start Timed Game (average)
+             Launch Solver Utility
+             View Stats
+             Quit
+
+"#;
+        assert_eq!(terminal.text(), expected_text);
+        assert_eq!(terminal.cell(1, 3).attrs, Attrs::GREEN);
+    }
+
+    #[test]
+    fn check_start_menu_wraps_cursor() {
+        let terminal = HeadlessTerminal::new(50, 18);
+        terminal.push_keys(&[Key::Up, Key::Char(' ')]);
+        assert!(run_start_menu(&KeyBindings::default(), &terminal).is_none());
+
+        // going up through all seven options comes back around to the first
+        let terminal = HeadlessTerminal::new(50, 18);
+        terminal.push_keys(&[Key::Char('k'); 7]);
+        terminal.push_keys(&[Key::Char('2'), Key::KeypadCenter]);
+        assert!(matches!(
+            run_start_menu(&KeyBindings::default(), &terminal),
+            Some(Screen::Game(board::Difficulty::Average, false))
+        ));
+    }
+
+    #[test]
+    fn check_start_menu_mouse() {
+        // the options start one row below the middle of the window
+        let terminal = HeadlessTerminal::new(50, 18);
+        let mouse = |action, y| Key::Mouse(MouseEvent { action, y, x: 20 });
+        terminal.push_keys(&[mouse(MouseAction::Hover, 14), Key::Enter]);
+        assert!(matches!(
+            run_start_menu(&KeyBindings::default(), &terminal),
+            Some(Screen::Solver)
+        ));
+
+        // clicking above the options leaves the cursor where it was
+        let terminal = HeadlessTerminal::new(50, 18);
+        terminal.push_keys(&[mouse(MouseAction::Click, 8), mouse(MouseAction::Click, 12)]);
+        assert!(matches!(
+            run_start_menu(&KeyBindings::default(), &terminal),
+            Some(Screen::Game(board::Difficulty::Hard, false))
+        ));
+    }
+
+    #[test]
+    fn check_start_menu_too_small() {
+        let terminal = HeadlessTerminal::new(30, 6);
+        terminal.push_keys(&[Key::Enter]);
+
+        run_start_menu(&KeyBindings::default(), &terminal);
+        let expected_text = r#"
+      Terminal too small
+          Need 44x16
+          Have 30x6
+
+
+"#;
+        assert_eq!(terminal.text(), expected_text);
+    }
+}
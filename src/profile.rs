@@ -0,0 +1,581 @@
+use crate::board::{Difficulty, MAX_ATTEMPTS};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// H_amming D_istance D_istribution Entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HDDEntry {
+    pub num_words: usize, // the number of words to look for with this hamming distance
+    pub hamming_distance: usize, // the hamming distance to look for
+}
+
+// Longer words wouldn't fit in the history column
+pub(crate) const MAX_WORD_LEN: usize = 16;
+/// Checking solvability builds a decision tree for every board generated, which gets too slow to
+/// retry with many more words than this
+pub const MAX_WORD_COUNT_WITH_SOLVABILITY: usize = 32;
+const MAX_ATTEMPTS_IN_PROFILE: usize = 10;
+pub(crate) const MIN_PANE_WIDTH: usize = 4;
+pub(crate) const MAX_PANE_WIDTH: usize = 32;
+pub(crate) const MIN_PANE_HEIGHT: usize = 4;
+pub(crate) const MAX_PANE_HEIGHT: usize = 64;
+/// The biggest dump any profile can make, two panes at the biggest size
+pub const MAX_DUMP_SIZE: usize = MAX_PANE_WIDTH * MAX_PANE_HEIGHT * 2;
+
+/// Everything which makes one game harder than another. The five difficulties each have a preset
+/// profile and changing any setting makes a custom one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DifficultyProfile {
+    pub difficulty: Difficulty,
+    pub word_len: usize,
+    pub word_count: usize, // including the password
+    pub attempts: usize,   // before lockout, each wrong guess uses one
+    /// Decoys are picked from each bucket in turn, closest to the password first. The last bucket
+    /// takes any words left over once the others are full.
+    pub distance_buckets: Vec<HDDEntry>,
+    pub pane_width: usize,        // characters per row of each hex dump pane
+    pub pane_height: usize,       // rows of each hex dump pane
+    pub bracket_sequences: usize, // fewer may fit
+    pub time_limit: Duration,     // for timed games
+    /// A cap on how hard boards get: only keep boards whose worst case for the best guessing
+    /// strategy is no more than this many attempts (≤ n). Being no more than the attempts
+    /// guarantees the board can be won.
+    pub min_solvability: Option<usize>,
+    /// A floor on how hard boards get: only keep boards whose worst case for the best guessing
+    /// strategy is at least this many attempts (≥ n), to make them deliberately tricky
+    pub max_solvability: Option<usize>,
+}
+
+#[derive(Debug)]
+pub enum ProfileErr {
+    ReadFile(PathBuf, std::io::Error),
+    InvalidLine(usize, String),
+    InvalidSetting(String),
+    WordLenOutOfRange(usize),
+    TooFewWords(usize),
+    AttemptsOutOfRange(usize),
+    NoDistanceBuckets,
+    EmptyDistanceBucket(usize),
+    DistanceOutOfRange {
+        distance: usize,
+        word_len: usize,
+    },
+    DistancesOutOfOrder,
+    PaneOutOfRange(usize, usize),
+    WordsDontFit {
+        word_count: usize,
+        word_len: usize,
+        dump_size: usize,
+    },
+    NoTimeLimit,
+    TooManyWordsForSolvability(usize),
+    SolvabilityOutOfRange(usize),
+    MinSolvabilityOfOne(usize),
+    SolvabilityAboveAttempts {
+        min_solvability: usize,
+        attempts: usize,
+    },
+    SolvabilitiesConflict {
+        min_solvability: usize,
+        max_solvability: usize,
+    },
+}
+
+impl std::fmt::Display for ProfileErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ProfileErr::ReadFile(path, err) => {
+                write!(f, "couldn't read profile {}: {}", path.display(), err)
+            }
+            ProfileErr::InvalidLine(line_num, line) => {
+                write!(f, "invalid profile line {}: '{}'", line_num, line)
+            }
+            ProfileErr::InvalidSetting(setting) => {
+                write!(f, "invalid profile setting '{}'", setting)
+            }
+            ProfileErr::WordLenOutOfRange(word_len) => write!(
+                f,
+                "word length {} isn't between 2 and {}",
+                word_len, MAX_WORD_LEN
+            ),
+            ProfileErr::TooFewWords(word_count) => write!(
+                f,
+                "{} word(s) isn't enough, the password needs at least one decoy",
+                word_count
+            ),
+            ProfileErr::AttemptsOutOfRange(attempts) => write!(
+                f,
+                "{} attempts isn't between 1 and {}",
+                attempts, MAX_ATTEMPTS_IN_PROFILE
+            ),
+            ProfileErr::NoDistanceBuckets => write!(f, "there are no distance buckets"),
+            ProfileErr::EmptyDistanceBucket(distance) => {
+                write!(f, "the bucket for distance {} has no words", distance)
+            }
+            ProfileErr::DistanceOutOfRange { distance, word_len } => write!(
+                f,
+                "distance {} isn't between 1 and the word length of {}",
+                distance, word_len
+            ),
+            ProfileErr::DistancesOutOfOrder => {
+                write!(f, "distance buckets have to go from closest to furthest")
+            }
+            ProfileErr::PaneOutOfRange(width, height) => write!(
+                f,
+                "a {}x{} pane isn't between {}x{} and {}x{}",
+                width, height, MIN_PANE_WIDTH, MIN_PANE_HEIGHT, MAX_PANE_WIDTH, MAX_PANE_HEIGHT
+            ),
+            ProfileErr::WordsDontFit {
+                word_count,
+                word_len,
+                dump_size,
+            } => write!(
+                f,
+                "{} words of {} letters don't fit in a hex dump of {} characters",
+                word_count, word_len, dump_size
+            ),
+            ProfileErr::NoTimeLimit => write!(f, "the time limit has to be at least a second"),
+            ProfileErr::TooManyWordsForSolvability(word_count) => write!(
+                f,
+                "solvability can only be limited with up to {} words, not {}",
+                MAX_WORD_COUNT_WITH_SOLVABILITY, word_count
+            ),
+            ProfileErr::SolvabilityOutOfRange(solvability) => write!(
+                f,
+                "a solvability of {} attempts isn't between 1 and the number of words",
+                solvability
+            ),
+            ProfileErr::MinSolvabilityOfOne(word_count) => write!(
+                f,
+                "a minimum solvability of 1 attempt needs the first guess to always be right, \
+                 which no board of {} words can promise",
+                word_count
+            ),
+            ProfileErr::SolvabilityAboveAttempts {
+                min_solvability,
+                attempts,
+            } => write!(
+                f,
+                "a minimum solvability of {} attempts can't guarantee a win with {} attempts",
+                min_solvability, attempts
+            ),
+            ProfileErr::SolvabilitiesConflict {
+                min_solvability,
+                max_solvability,
+            } => write!(
+                f,
+                "no board needs at least {} attempts and can always be solved in {}",
+                max_solvability, min_solvability
+            ),
+        }
+    }
+}
+
+// "d:n" for n words at hamming distance d
+fn parse_distance_bucket(text: &str) -> Option<HDDEntry> {
+    let (distance, num_words) = text.split_once(':')?;
+    Some(HDDEntry {
+        num_words: num_words.parse().ok()?,
+        hamming_distance: distance.parse().ok()?,
+    })
+}
+
+// "WxH"
+fn parse_pane_size(text: &str) -> Option<(usize, usize)> {
+    let (width, height) = text.split_once('x')?;
+    Some((width.parse().ok()?, height.parse().ok()?))
+}
+
+impl DifficultyProfile {
+    /// Custom profiles start out the same as average
+    pub fn preset(difficulty: Difficulty) -> Self {
+        let (word_len, distances, time_limit_secs) = match difficulty {
+            Difficulty::VeryEasy => (4, [1, 2, 3, 4], 60),
+            Difficulty::Easy => (6, [1, 3, 4, 5], 90),
+            Difficulty::Average | Difficulty::Custom => (8, [1, 3, 5, 7], 120),
+            Difficulty::Hard => (10, [1, 4, 6, 9], 150),
+            Difficulty::VeryHard => (12, [1, 3, 7, 10], 180),
+        };
+
+        let bucket_sizes = [1, 2, 3, 5];
+        DifficultyProfile {
+            difficulty,
+            word_len,
+            word_count: 12,
+            attempts: MAX_ATTEMPTS,
+            distance_buckets: bucket_sizes
+                .iter()
+                .zip(distances.iter())
+                .map(|(num_words, hamming_distance)| HDDEntry {
+                    num_words: *num_words,
+                    hamming_distance: *hamming_distance,
+                })
+                .collect(),
+            pane_width: 12,
+            pane_height: 16,
+            bracket_sequences: 8,
+            time_limit: Duration::from_secs(time_limit_secs),
+            min_solvability: None,
+            max_solvability: None,
+        }
+    }
+
+    pub fn dump_size(&self) -> usize {
+        self.pane_width * self.pane_height * 2 // 2 dump panes
+    }
+
+    /// Change one setting, written as the setting's name and then its value, e.g. "pane 16x12".
+    /// Distance buckets are all given at once, e.g. "distances 1:1 3:2 5:3 7:5".
+    pub fn apply_setting(&mut self, setting: &str) -> Result<(), ProfileErr> {
+        let invalid_setting = || ProfileErr::InvalidSetting(String::from(setting));
+        let parse_count = |value: &str| value.parse::<usize>().map_err(|_| invalid_setting());
+
+        let fields: Vec<&str> = setting.split_whitespace().collect();
+        match fields.as_slice() {
+            ["word_len", value] => self.word_len = parse_count(value)?,
+            ["words", value] => self.word_count = parse_count(value)?,
+            ["attempts", value] => self.attempts = parse_count(value)?,
+            ["distances", buckets @ ..] if !buckets.is_empty() => {
+                self.distance_buckets = buckets
+                    .iter()
+                    .map(|bucket| parse_distance_bucket(bucket))
+                    .collect::<Option<Vec<HDDEntry>>>()
+                    .ok_or_else(invalid_setting)?;
+            }
+            ["pane", value] => {
+                let (width, height) = parse_pane_size(value).ok_or_else(invalid_setting)?;
+                self.pane_width = width;
+                self.pane_height = height;
+            }
+            ["brackets", value] => self.bracket_sequences = parse_count(value)?,
+            ["time_limit", value] => {
+                self.time_limit = Duration::from_secs(parse_count(value)? as u64);
+            }
+            ["min_solvability", value] => self.min_solvability = Some(parse_count(value)?),
+            ["max_solvability", value] => self.max_solvability = Some(parse_count(value)?),
+            _ => return Err(invalid_setting()),
+        }
+
+        self.difficulty = Difficulty::Custom;
+        Ok(())
+    }
+
+    /// One setting per line. Blank lines and lines starting with # are skipped. The solvability
+    /// settings bound the attempts the best guessing strategy needs in its worst case, with
+    /// "min_solvability n" capping it at n or fewer and "max_solvability n" needing n or more.
+    pub fn apply_settings(&mut self, text: &str) -> Result<(), ProfileErr> {
+        for (i, line) in text.lines().map(|line| line.trim()).enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            self.apply_setting(line)
+                .map_err(|_| ProfileErr::InvalidLine(i + 1, String::from(line)))?;
+        }
+        Ok(())
+    }
+
+    /// Apply the settings in a profile file on top of this profile
+    pub fn apply_file(&mut self, path: &Path) -> Result<(), ProfileErr> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| ProfileErr::ReadFile(path.to_path_buf(), e))?;
+        self.apply_settings(&text)
+    }
+
+    /// Every setting, in the form apply_settings reads. The worst case limits are left out when
+    /// they aren't set.
+    pub fn settings(&self) -> Vec<String> {
+        let distances: Vec<String> = self
+            .distance_buckets
+            .iter()
+            .map(|bucket| format!("{}:{}", bucket.hamming_distance, bucket.num_words))
+            .collect();
+        let mut settings = vec![
+            format!("word_len {}", self.word_len),
+            format!("words {}", self.word_count),
+            format!("attempts {}", self.attempts),
+            format!("distances {}", distances.join(" ")),
+            format!("pane {}x{}", self.pane_width, self.pane_height),
+            format!("brackets {}", self.bracket_sequences),
+            format!("time_limit {}", self.time_limit.as_secs()),
+        ];
+        if let Some(min_solvability) = self.min_solvability {
+            settings.push(format!("min_solvability {}", min_solvability));
+        }
+        if let Some(max_solvability) = self.max_solvability {
+            settings.push(format!("max_solvability {}", max_solvability));
+        }
+        settings
+    }
+
+    /// Catch settings the game can't be played with before it starts
+    pub fn validate(&self) -> Result<(), ProfileErr> {
+        if self.word_len < 2 || self.word_len > MAX_WORD_LEN {
+            return Err(ProfileErr::WordLenOutOfRange(self.word_len));
+        }
+        if self.word_count < 2 {
+            return Err(ProfileErr::TooFewWords(self.word_count));
+        }
+        if self.attempts < 1 || self.attempts > MAX_ATTEMPTS_IN_PROFILE {
+            return Err(ProfileErr::AttemptsOutOfRange(self.attempts));
+        }
+
+        if self.distance_buckets.is_empty() {
+            return Err(ProfileErr::NoDistanceBuckets);
+        }
+        for bucket in &self.distance_buckets {
+            if bucket.num_words == 0 {
+                return Err(ProfileErr::EmptyDistanceBucket(bucket.hamming_distance));
+            }
+            if bucket.hamming_distance < 1 || bucket.hamming_distance > self.word_len {
+                return Err(ProfileErr::DistanceOutOfRange {
+                    distance: bucket.hamming_distance,
+                    word_len: self.word_len,
+                });
+            }
+        }
+        // words are picked going out from the password so the buckets can't go back in
+        let in_order = self
+            .distance_buckets
+            .windows(2)
+            .all(|pair| pair[0].hamming_distance < pair[1].hamming_distance);
+        if !in_order {
+            return Err(ProfileErr::DistancesOutOfOrder);
+        }
+
+        let pane_width_in_range = (MIN_PANE_WIDTH..=MAX_PANE_WIDTH).contains(&self.pane_width);
+        let pane_height_in_range = (MIN_PANE_HEIGHT..=MAX_PANE_HEIGHT).contains(&self.pane_height);
+        if !pane_width_in_range || !pane_height_in_range {
+            return Err(ProfileErr::PaneOutOfRange(
+                self.pane_width,
+                self.pane_height,
+            ));
+        }
+        if self.word_count * self.word_len > self.dump_size() {
+            return Err(ProfileErr::WordsDontFit {
+                word_count: self.word_count,
+                word_len: self.word_len,
+                dump_size: self.dump_size(),
+            });
+        }
+
+        if self.time_limit.as_secs() == 0 {
+            return Err(ProfileErr::NoTimeLimit);
+        }
+
+        let has_solvability_limit =
+            self.min_solvability.is_some() || self.max_solvability.is_some();
+        if has_solvability_limit && self.word_count > MAX_WORD_COUNT_WITH_SOLVABILITY {
+            return Err(ProfileErr::TooManyWordsForSolvability(self.word_count));
+        }
+        // the best strategy always needs at least one guess and never more than one per word
+        for solvability in self.min_solvability.iter().chain(&self.max_solvability) {
+            if *solvability < 1 || *solvability > self.word_count {
+                return Err(ProfileErr::SolvabilityOutOfRange(*solvability));
+            }
+        }
+        if let Some(min_solvability) = self.min_solvability {
+            // with more than one word the first guess can always be a decoy
+            if min_solvability == 1 && self.word_count >= 2 {
+                return Err(ProfileErr::MinSolvabilityOfOne(self.word_count));
+            }
+            if min_solvability > self.attempts {
+                return Err(ProfileErr::SolvabilityAboveAttempts {
+                    min_solvability,
+                    attempts: self.attempts,
+                });
+            }
+        }
+        if let (Some(min_solvability), Some(max_solvability)) =
+            (self.min_solvability, self.max_solvability)
+        {
+            if max_solvability > min_solvability {
+                return Err(ProfileErr::SolvabilitiesConflict {
+                    min_solvability,
+                    max_solvability,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::ALL_DIFFICULTIES;
+
+    #[test]
+    fn check_presets_are_valid() {
+        for difficulty in &ALL_DIFFICULTIES {
+            let profile = DifficultyProfile::preset(*difficulty);
+            assert_eq!(profile.difficulty, *difficulty);
+            assert!(profile.validate().is_ok());
+        }
+
+        // timed games get longer the harder they are
+        let time_limits: Vec<_> = ALL_DIFFICULTIES
+            .iter()
+            .filter(|d| **d != Difficulty::Custom)
+            .map(|d| DifficultyProfile::preset(*d).time_limit)
+            .collect();
+        assert!(time_limits.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn check_apply_settings() {
+        let mut profile = DifficultyProfile::preset(Difficulty::Hard);
+        profile
+            .apply_settings(
+                "# a shorter game\n\nwords 8\ndistances 2:3 4:4\npane 16x10\nmin_solvability 3\n",
+            )
+            .unwrap();
+        assert_eq!(profile.difficulty, Difficulty::Custom);
+        assert_eq!(profile.word_len, 10);
+        assert_eq!(profile.word_count, 8);
+        assert_eq!(
+            profile.distance_buckets,
+            [
+                HDDEntry {
+                    num_words: 3,
+                    hamming_distance: 2,
+                },
+                HDDEntry {
+                    num_words: 4,
+                    hamming_distance: 4,
+                },
+            ]
+        );
+        assert_eq!((profile.pane_width, profile.pane_height), (16, 10));
+        assert_eq!(profile.min_solvability, Some(3));
+
+        // the settings read back in to the same profile
+        let mut read_back = DifficultyProfile::preset(Difficulty::Custom);
+        read_back
+            .apply_settings(&profile.settings().join("\n"))
+            .unwrap();
+        assert_eq!(read_back, profile);
+
+        assert!(matches!(
+            profile.apply_settings("attempts 3\nlives 9"),
+            Err(ProfileErr::InvalidLine(2, _))
+        ));
+        assert!(matches!(
+            profile.apply_setting("pane 16"),
+            Err(ProfileErr::InvalidSetting(_))
+        ));
+        assert!(matches!(
+            profile.apply_setting("distances 1:x"),
+            Err(ProfileErr::InvalidSetting(_))
+        ));
+    }
+
+    #[test]
+    fn check_validate() {
+        let invalid = |setting: &str| {
+            let mut profile = DifficultyProfile::preset(Difficulty::Average);
+            profile.apply_setting(setting).unwrap();
+            profile.validate().unwrap_err()
+        };
+
+        assert!(matches!(
+            invalid("word_len 1"),
+            ProfileErr::WordLenOutOfRange(1)
+        ));
+        assert!(matches!(invalid("words 1"), ProfileErr::TooFewWords(1)));
+        assert!(matches!(
+            invalid("attempts 0"),
+            ProfileErr::AttemptsOutOfRange(0)
+        ));
+        assert!(matches!(
+            invalid("distances 1:1 9:4"),
+            ProfileErr::DistanceOutOfRange {
+                distance: 9,
+                word_len: 8
+            }
+        ));
+        assert!(matches!(
+            invalid("distances 0:1 3:4"),
+            ProfileErr::DistanceOutOfRange { distance: 0, .. }
+        ));
+        assert!(matches!(
+            invalid("distances 3:1 2:4"),
+            ProfileErr::DistancesOutOfOrder
+        ));
+        assert!(matches!(
+            invalid("distances 3:0"),
+            ProfileErr::EmptyDistanceBucket(3)
+        ));
+        assert!(matches!(
+            invalid("pane 100x16"),
+            ProfileErr::PaneOutOfRange(100, 16)
+        ));
+        assert!(matches!(
+            invalid("pane 4x4"),
+            ProfileErr::WordsDontFit {
+                word_count: 12,
+                word_len: 8,
+                dump_size: 32
+            }
+        ));
+        assert!(matches!(invalid("time_limit 0"), ProfileErr::NoTimeLimit));
+        assert!(matches!(
+            invalid("max_solvability 0"),
+            ProfileErr::SolvabilityOutOfRange(0)
+        ));
+        assert!(matches!(
+            invalid("max_solvability 13"),
+            ProfileErr::SolvabilityOutOfRange(13)
+        ));
+        assert!(matches!(
+            invalid("min_solvability 1"),
+            ProfileErr::MinSolvabilityOfOne(12)
+        ));
+        assert!(matches!(
+            invalid("min_solvability 5"),
+            ProfileErr::SolvabilityAboveAttempts {
+                min_solvability: 5,
+                attempts: 4
+            }
+        ));
+
+        let with_settings = |settings: &str| {
+            let mut profile = DifficultyProfile::preset(Difficulty::Average);
+            profile.apply_settings(settings).unwrap();
+            profile.validate()
+        };
+        assert!(matches!(
+            with_settings("min_solvability 3\nmax_solvability 4"),
+            Err(ProfileErr::SolvabilitiesConflict {
+                min_solvability: 3,
+                max_solvability: 4
+            })
+        ));
+        assert!(with_settings("min_solvability 4\nmax_solvability 4").is_ok());
+        assert!(matches!(
+            with_settings("pane 32x32\nwords 33\nmax_solvability 4"),
+            Err(ProfileErr::TooManyWordsForSolvability(33))
+        ));
+        assert!(with_settings("pane 32x32\nwords 33").is_ok());
+    }
+
+    #[test]
+    fn ensure_word_len_for_difficulty_matches_hamming_distance_distribution_for_difficulty() {
+        let difficulties = [
+            Difficulty::VeryEasy,
+            Difficulty::Easy,
+            Difficulty::Average,
+            Difficulty::Hard,
+            Difficulty::VeryHard,
+        ];
+
+        for d in &difficulties {
+            let profile = DifficultyProfile::preset(*d);
+
+            for hdd_entry in &profile.distance_buckets {
+                assert!(hdd_entry.hamming_distance <= profile.word_len);
+            }
+        }
+    }
+}
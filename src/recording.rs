@@ -0,0 +1,497 @@
+use crate::game::{GameInput, InputSource};
+use crate::input::{input_cmd_name, parse_input_cmd, InputCmd, KeyBindings};
+use crate::tui::Terminal;
+use fonv_cracker::board::{Board, BoardErr, Difficulty};
+use fonv_cracker::game_state::GameCmd;
+use fonv_cracker::profile::{DifficultyProfile, ProfileErr};
+use std::time::{Duration, Instant};
+
+// Bump the version whenever games an older version could also play get recorded in a way it can't
+// read, e.g. v2 added "move_to" lines for mouse input. v1 recordings only hold keyboard input,
+// which v2 reads the same way, so they still play back.
+const RECORDING_HEADER_PREFIX: &str = "fonv_cracker recording ";
+const RECORDING_HEADER: &str = "fonv_cracker recording v2";
+const RECORDING_HEADER_V1: &str = "fonv_cracker recording v1";
+
+// Everything needed to play a game back: the board as it was dealt, since generated ones also depend
+// on the dictionary, and the player's commands timestamped from the start of the game. Recordings
+// from before boards were always kept generate theirs again from the difficulty profile and seed.
+// Mouse input is kept as where it put the cursor rather than where it was on the screen.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Recording {
+    pub profile: DifficultyProfile,
+    pub board: Option<Board>,
+    pub seed: u64,
+    pub time_limit: Option<Duration>,
+    pub inputs: Vec<(Duration, GameCmd)>,
+}
+
+#[derive(Debug)]
+pub enum RecordingErr {
+    ReadFile(String, std::io::Error),
+    WriteFile(String, std::io::Error),
+    MissingHeader,
+    UnsupportedVersion(String),
+    InvalidLine(usize, String),
+    MissingDifficulty,
+    MissingSeed,
+    InvalidProfile(ProfileErr),
+    InvalidBoard(BoardErr),
+}
+
+impl std::fmt::Display for RecordingErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RecordingErr::ReadFile(file, err) => {
+                write!(f, "couldn't read recording {}: {}", file, err)
+            }
+            RecordingErr::WriteFile(file, err) => {
+                write!(f, "couldn't write recording {}: {}", file, err)
+            }
+            RecordingErr::MissingHeader => {
+                write!(f, "the file doesn't start with '{}'", RECORDING_HEADER)
+            }
+            RecordingErr::UnsupportedVersion(version) => write!(
+                f,
+                "recording version '{}' can't be played back, expected '{}'",
+                version, RECORDING_HEADER
+            ),
+            RecordingErr::InvalidLine(line_num, line) => {
+                write!(f, "invalid recording line {}: '{}'", line_num, line)
+            }
+            RecordingErr::MissingDifficulty => write!(f, "the recording has no difficulty"),
+            RecordingErr::MissingSeed => write!(f, "the recording has no seed"),
+            RecordingErr::InvalidProfile(err) => {
+                write!(f, "the recording's difficulty profile is invalid: {}", err)
+            }
+            RecordingErr::InvalidBoard(err) => {
+                write!(f, "the recording's board is invalid: {}", err)
+            }
+        }
+    }
+}
+
+impl Recording {
+    pub fn new(
+        profile: DifficultyProfile,
+        board: Option<Board>,
+        seed: u64,
+        time_limit: Option<Duration>,
+    ) -> Self {
+        Recording {
+            profile,
+            board,
+            seed,
+            time_limit,
+            inputs: Vec::new(),
+        }
+    }
+
+    // The clock isn't recorded, replays tick along on their own
+    pub fn push_input(&mut self, time: Duration, game_cmd: GameCmd) {
+        if let GameCmd::Tick(_) = game_cmd {
+            return;
+        }
+        self.inputs.push((time, game_cmd));
+    }
+
+    // One setting or input per line, e.g. "input 1520 select" for a selection 1.52s into the game.
+    // Wherever the mouse put the cursor is kept as its pane, row and column, e.g.
+    // "input 980 move_to 1 7 4".
+    // Custom difficulties list every profile setting, e.g. "profile pane 16x12". The board is
+    // kept a line at a time in its own format, e.g. "board word 37 apple".
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "{}\ndifficulty {}\nseed {}\n",
+            RECORDING_HEADER, self.profile.difficulty, self.seed
+        );
+        if self.profile.difficulty == Difficulty::Custom {
+            for setting in self.profile.settings() {
+                text.push_str(&format!("profile {}\n", setting));
+            }
+        }
+        if let Some(board) = &self.board {
+            for board_line in board.to_text().lines() {
+                text.push_str(&format!("board {}\n", board_line));
+            }
+        }
+        // untimed games just leave the time limit out, which also keeps older recordings loading
+        if let Some(time_limit) = self.time_limit {
+            text.push_str(&format!("time_limit {}\n", time_limit.as_millis()));
+        }
+        for (time, game_cmd) in &self.inputs {
+            let cmd_text = match *game_cmd {
+                GameCmd::Move(movement) => String::from(input_cmd_name(InputCmd::Move(movement))),
+                GameCmd::MoveTo {
+                    pane_num,
+                    row_num,
+                    col,
+                } => format!("move_to {} {} {}", pane_num, row_num, col),
+                GameCmd::Select => String::from(input_cmd_name(InputCmd::Select)),
+                GameCmd::Quit => String::from(input_cmd_name(InputCmd::Quit)),
+                GameCmd::Tick(_) => unreachable!("ticks aren't recorded"),
+            };
+            text.push_str(&format!("input {} {}\n", time.as_millis(), cmd_text));
+        }
+        text
+    }
+
+    pub fn from_text(text: &str) -> Result<Self, RecordingErr> {
+        let mut lines = text.lines().map(|line| line.trim());
+        match lines.next() {
+            Some(RECORDING_HEADER) | Some(RECORDING_HEADER_V1) => (),
+            Some(header) if header.starts_with(RECORDING_HEADER_PREFIX) => {
+                return Err(RecordingErr::UnsupportedVersion(String::from(
+                    &header[RECORDING_HEADER_PREFIX.len()..],
+                )));
+            }
+            _ => return Err(RecordingErr::MissingHeader),
+        }
+
+        let mut difficulty = None;
+        let mut profile_settings = Vec::new();
+        let mut board_lines = Vec::new();
+        let mut seed = None;
+        let mut time_limit = None;
+        let mut inputs = Vec::new();
+        for (i, line) in lines.enumerate() {
+            // the header was line 1
+            let line_num = i + 2;
+            let invalid_line = || RecordingErr::InvalidLine(line_num, String::from(line));
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                [] => (),
+                ["difficulty", d] => difficulty = Some(d.parse().map_err(|_| invalid_line())?),
+                ["profile", setting @ ..] => profile_settings.push((line_num, setting.join(" "))),
+                ["board", board_line @ ..] => board_lines.push(board_line.join(" ")),
+                ["seed", s] => seed = Some(s.parse().map_err(|_| invalid_line())?),
+                ["time_limit", millis] => {
+                    let millis = millis.parse().map_err(|_| invalid_line())?;
+                    time_limit = Some(Duration::from_millis(millis));
+                }
+                ["input", millis, cmd] => {
+                    let millis = millis.parse().map_err(|_| invalid_line())?;
+                    let game_cmd = match parse_input_cmd(cmd) {
+                        Some(InputCmd::Move(movement)) => GameCmd::Move(movement),
+                        Some(InputCmd::Select) => GameCmd::Select,
+                        Some(InputCmd::Quit) => GameCmd::Quit,
+                        Some(InputCmd::Mouse(_)) | None => return Err(invalid_line()),
+                    };
+                    inputs.push((Duration::from_millis(millis), game_cmd));
+                }
+                ["input", millis, "move_to", pane_num, row_num, col] => {
+                    let millis = millis.parse().map_err(|_| invalid_line())?;
+                    let game_cmd = GameCmd::MoveTo {
+                        pane_num: pane_num.parse().map_err(|_| invalid_line())?,
+                        row_num: row_num.parse().map_err(|_| invalid_line())?,
+                        col: col.parse().map_err(|_| invalid_line())?,
+                    };
+                    inputs.push((Duration::from_millis(millis), game_cmd));
+                }
+                _ => return Err(invalid_line()),
+            }
+        }
+
+        let mut profile =
+            DifficultyProfile::preset(difficulty.ok_or(RecordingErr::MissingDifficulty)?);
+        for (line_num, setting) in &profile_settings {
+            profile.apply_setting(setting).map_err(|_| {
+                RecordingErr::InvalidLine(*line_num, format!("profile {}", setting))
+            })?;
+        }
+        profile.validate().map_err(RecordingErr::InvalidProfile)?;
+        let board = if board_lines.is_empty() {
+            None
+        } else {
+            Some(Board::from_text(&board_lines.join("\n")).map_err(RecordingErr::InvalidBoard)?)
+        };
+
+        Ok(Recording {
+            profile,
+            board,
+            seed: seed.ok_or(RecordingErr::MissingSeed)?,
+            time_limit,
+            inputs,
+        })
+    }
+
+    pub fn load(file: &str) -> Result<Self, RecordingErr> {
+        let text = std::fs::read_to_string(file)
+            .map_err(|e| RecordingErr::ReadFile(String::from(file), e))?;
+        Self::from_text(&text)
+    }
+
+    pub fn save(&self, file: &str) -> Result<(), RecordingErr> {
+        std::fs::write(file, self.to_text())
+            .map_err(|e| RecordingErr::WriteFile(String::from(file), e))
+    }
+}
+
+// Feeds a recording's commands back to the game at the same points in time they were first made.
+// The quit key still works to stop watching early.
+pub struct ReplayInputSource<'a> {
+    inputs: Vec<(Duration, GameCmd)>,
+    next_input: usize,
+    // the game polls for the first time as it starts so the inputs are timed from then rather than
+    // from before its board was generated
+    start: Option<Instant>,
+    clock: Box<dyn Fn() -> Instant + 'a>,
+    key_bindings: &'a KeyBindings,
+    window: &'a dyn Terminal,
+}
+
+impl<'a> ReplayInputSource<'a> {
+    pub fn new(
+        recording: Recording,
+        key_bindings: &'a KeyBindings,
+        window: &'a dyn Terminal,
+    ) -> Self {
+        Self::with_clock(recording, Box::new(Instant::now), key_bindings, window)
+    }
+
+    fn with_clock(
+        recording: Recording,
+        clock: Box<dyn Fn() -> Instant + 'a>,
+        key_bindings: &'a KeyBindings,
+        window: &'a dyn Terminal,
+    ) -> Self {
+        ReplayInputSource {
+            inputs: recording.inputs,
+            next_input: 0,
+            start: None,
+            clock,
+            key_bindings,
+            window,
+        }
+    }
+}
+
+impl<'a> InputSource for ReplayInputSource<'a> {
+    fn poll_input(&mut self) -> Option<GameInput> {
+        let polled_key = self.window.poll_key();
+        if polled_key.and_then(|key| self.key_bindings.input_cmd(key)) == Some(InputCmd::Quit) {
+            return Some(GameInput::Player(InputCmd::Quit));
+        }
+
+        let now = (self.clock)();
+        let start = *self.start.get_or_insert(now);
+        match self.inputs.get(self.next_input) {
+            Some((time, game_cmd)) if now - start >= *time => {
+                self.next_input += 1;
+                Some(GameInput::Replayed(*game_cmd))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::headless::HeadlessTerminal;
+    use fonv_cracker::game_state::Movement;
+
+    #[test]
+    fn check_recording_round_trip() {
+        let mut recording = Recording::new(
+            DifficultyProfile::preset(Difficulty::Hard),
+            None,
+            1234,
+            None,
+        );
+        recording.push_input(Duration::from_millis(250), GameCmd::Move(Movement::Down));
+        recording.push_input(Duration::from_millis(900), GameCmd::Move(Movement::Right));
+        recording.push_input(Duration::from_millis(1520), GameCmd::Select);
+        let move_to = GameCmd::MoveTo {
+            pane_num: 1,
+            row_num: 7,
+            col: 4,
+        };
+        recording.push_input(Duration::from_millis(2100), move_to);
+        recording.push_input(Duration::from_millis(2100), GameCmd::Select);
+        recording.push_input(
+            Duration::from_millis(3000),
+            GameCmd::Tick(Duration::from_secs(3)),
+        );
+        recording.push_input(Duration::from_millis(4000), GameCmd::Quit);
+
+        let text = recording.to_text();
+        assert_eq!(
+            text,
+            "fonv_cracker recording v2\ndifficulty Hard\nseed 1234\n\
+             input 250 down\ninput 900 right\ninput 1520 select\ninput 2100 move_to 1 7 4\n\
+             input 2100 select\ninput 4000 quit\n"
+        );
+        assert_eq!(Recording::from_text(&text).unwrap(), recording);
+
+        let mut timed_recording = Recording::new(
+            DifficultyProfile::preset(Difficulty::Easy),
+            None,
+            99,
+            Some(Duration::from_secs(90)),
+        );
+        timed_recording.push_input(Duration::from_millis(1520), GameCmd::Select);
+        let text = timed_recording.to_text();
+        assert_eq!(
+            text,
+            "fonv_cracker recording v2\ndifficulty Easy\nseed 99\ntime_limit 90000\n\
+             input 1520 select\n"
+        );
+        assert_eq!(Recording::from_text(&text).unwrap(), timed_recording);
+
+        // v1 recordings only have key presses, which read the same way
+        let mut v1_recording = Recording::new(
+            DifficultyProfile::preset(Difficulty::Average),
+            None,
+            5,
+            None,
+        );
+        v1_recording.push_input(Duration::from_millis(300), GameCmd::Move(Movement::Left));
+        v1_recording.push_input(Duration::from_millis(800), GameCmd::Select);
+        assert_eq!(
+            Recording::from_text(
+                "fonv_cracker recording v1\ndifficulty Average\nseed 5\n\
+                 input 300 left\ninput 800 select\n"
+            )
+            .unwrap(),
+            v1_recording
+        );
+    }
+
+    #[test]
+    fn check_custom_profile_round_trip() {
+        let mut profile = DifficultyProfile::preset(Difficulty::Average);
+        profile.apply_setting("attempts 6").unwrap();
+        let recording = Recording::new(profile, None, 7, None);
+
+        let text = recording.to_text();
+        assert!(text.starts_with(
+            "fonv_cracker recording v2\ndifficulty Custom\nseed 7\nprofile word_len 8\n"
+        ));
+        assert!(text.contains("\nprofile attempts 6\n"));
+        assert_eq!(Recording::from_text(&text).unwrap(), recording);
+
+        assert!(matches!(
+            Recording::from_text(
+                "fonv_cracker recording v2\ndifficulty Custom\nseed 1\nprofile attempts 0\n"
+            ),
+            Err(RecordingErr::InvalidProfile(_))
+        ));
+        assert!(matches!(
+            Recording::from_text(
+                "fonv_cracker recording v2\ndifficulty Custom\nseed 1\nprofile lives 9\n"
+            ),
+            Err(RecordingErr::InvalidLine(4, _))
+        ));
+    }
+
+    #[test]
+    fn check_loaded_board_round_trip() {
+        let board = Board::from_text(
+            "fonv_cracker board v1\npane 4x4\nstart_addr 0xCC10\nsolution grape\n\
+             word 1 apple\nword 16 grape\nbrackets 8 4\n\
+             row #app\nrow le$%\nrow <#$>\nrow %%%%\nrow grap\nrow e%%%\nrow %%%%\nrow %%%%\n",
+        )
+        .unwrap();
+        let mut recording = Recording::new(
+            DifficultyProfile::preset(Difficulty::Custom),
+            Some(board),
+            3,
+            None,
+        );
+        recording.push_input(Duration::from_millis(500), GameCmd::Select);
+
+        let text = recording.to_text();
+        assert!(text.contains("\nboard fonv_cracker board v1\nboard pane 4x4\n"));
+        assert!(text.contains("\nboard word 16 grape\n"));
+        assert_eq!(Recording::from_text(&text).unwrap(), recording);
+
+        assert!(matches!(
+            Recording::from_text(
+                "fonv_cracker recording v2\ndifficulty Hard\nseed 1\nboard fonv_cracker board v1\n"
+            ),
+            Err(RecordingErr::InvalidBoard(BoardErr::MissingPane))
+        ));
+    }
+
+    #[test]
+    fn check_replay_times_inputs_from_first_poll() {
+        let mut recording =
+            Recording::new(DifficultyProfile::preset(Difficulty::Easy), None, 1, None);
+        recording.push_input(Duration::from_millis(200), GameCmd::Select);
+        recording.push_input(Duration::from_millis(450), GameCmd::Quit);
+        let key_bindings = KeyBindings::default();
+        let terminal = HeadlessTerminal::new(20, 5);
+        let clock_start = Instant::now();
+        let elapsed = std::cell::Cell::new(Duration::from_millis(300));
+        let mut replay = ReplayInputSource::with_clock(
+            recording,
+            Box::new(|| clock_start + elapsed.get()),
+            &key_bindings,
+            &terminal,
+        );
+        let mut poll_at = |millis| {
+            elapsed.set(Duration::from_millis(millis));
+            replay.poll_input()
+        };
+
+        // time spent before the game starts polling, e.g. generating its board, doesn't count
+        assert_eq!(poll_at(300), None);
+        assert_eq!(poll_at(499), None);
+        assert_eq!(poll_at(500), Some(GameInput::Replayed(GameCmd::Select)));
+        assert_eq!(poll_at(500), None);
+        assert_eq!(poll_at(749), None);
+        assert_eq!(poll_at(760), Some(GameInput::Replayed(GameCmd::Quit)));
+        assert_eq!(poll_at(2000), None);
+    }
+
+    #[test]
+    fn check_invalid_recordings() {
+        assert!(matches!(
+            Recording::from_text("difficulty Hard\nseed 1\n"),
+            Err(RecordingErr::MissingHeader)
+        ));
+        assert!(matches!(
+            Recording::from_text("fonv_cracker recording v3\ndifficulty Hard\nseed 1\n"),
+            Err(RecordingErr::UnsupportedVersion(version)) if version == "v3"
+        ));
+        assert!(matches!(
+            Recording::from_text("fonv_cracker recording v2\nseed 1\n"),
+            Err(RecordingErr::MissingDifficulty)
+        ));
+        assert!(matches!(
+            Recording::from_text("fonv_cracker recording v2\ndifficulty Hard\n"),
+            Err(RecordingErr::MissingSeed)
+        ));
+        assert!(matches!(
+            Recording::from_text(
+                "fonv_cracker recording v2\ndifficulty Hard\nseed 1\ninput 5 jump\n"
+            ),
+            Err(RecordingErr::InvalidLine(4, _))
+        ));
+        assert!(matches!(
+            Recording::from_text(
+                "fonv_cracker recording v2\ndifficulty Hard\nseed 1\ninput 5 select 7 14\n"
+            ),
+            Err(RecordingErr::InvalidLine(4, _))
+        ));
+        // screen positions of the mouse aren't kept, only where it put the cursor
+        assert!(matches!(
+            Recording::from_text(
+                "fonv_cracker recording v2\ndifficulty Hard\nseed 1\ninput 5 click 7 14\n"
+            ),
+            Err(RecordingErr::InvalidLine(4, _))
+        ));
+        assert!(matches!(
+            Recording::from_text(
+                "fonv_cracker recording v2\ndifficulty Hard\nseed 1\ninput 5 move_to 1 x 4\n"
+            ),
+            Err(RecordingErr::InvalidLine(4, _))
+        ));
+        assert!(matches!(
+            Recording::from_text("fonv_cracker recording v2\ndifficulty Nope\nseed 1\n"),
+            Err(RecordingErr::InvalidLine(2, _))
+        ));
+    }
+}
@@ -0,0 +1,343 @@
+//! An agent which plays games through [`GameState`] the way a player would, guessing words with a
+//! pluggable strategy, and a benchmark which plays it against many generated boards. It never
+//! selects bracket sequences so the results only reflect how hard the words are to tell apart.
+
+use crate::board::{Board, GenerateErr};
+use crate::dict::dict::{DictSource, EnglishDictChunk};
+use crate::game_state::{GameCmd, GameEvent, GameOutcome, GameState, SelectedChunk};
+use crate::profile::{DifficultyProfile, MAX_WORD_COUNT_WITH_SOLVABILITY};
+use crate::solver::{filter_matching_passwords, score_guess, KnownGuess};
+use crate::utils::seeded_rng::SeededRangeRng;
+use snm_rand_utils::range_rng::RangeRng;
+
+/// Picks which word to guess next
+pub trait GuessStrategy {
+    fn name(&self) -> &'static str;
+
+    /// Pick one of the words which haven't been guessed yet. The candidates are the ones which
+    /// could still be the password given the likeness of every guess so far.
+    fn pick_guess(&mut self, unguessed_words: &[String], candidates: &[String]) -> String;
+}
+
+/// Guesses any word it hasn't tried yet, ignoring the likenesses
+pub struct RandomStrategy {
+    rng: Box<dyn RangeRng<usize>>,
+}
+
+impl RandomStrategy {
+    pub fn new(rng: Box<dyn RangeRng<usize>>) -> Self {
+        RandomStrategy { rng }
+    }
+}
+
+impl GuessStrategy for RandomStrategy {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn pick_guess(&mut self, unguessed_words: &[String], _candidates: &[String]) -> String {
+        unguessed_words[self.rng.gen_range(0, unguessed_words.len())].clone()
+    }
+}
+
+/// Guesses the first word in the hex dump which could still be the password
+pub struct FirstConsistentStrategy;
+
+impl GuessStrategy for FirstConsistentStrategy {
+    fn name(&self) -> &'static str {
+        "first-consistent"
+    }
+
+    fn pick_guess(&mut self, _unguessed_words: &[String], candidates: &[String]) -> String {
+        candidates[0].clone()
+    }
+}
+
+/// Guesses whichever word leaves the fewest candidates in the worst case, preferring words which
+/// could still be the password when guesses score the same
+pub struct MinimaxStrategy;
+
+impl GuessStrategy for MinimaxStrategy {
+    fn name(&self) -> &'static str {
+        "minimax"
+    }
+
+    fn pick_guess(&mut self, unguessed_words: &[String], candidates: &[String]) -> String {
+        unguessed_words
+            .iter()
+            .min_by_key(|word| {
+                let is_candidate = candidates.contains(word);
+                (score_guess(word, candidates), !is_candidate)
+            })
+            .unwrap()
+            .clone()
+    }
+}
+
+pub const STRATEGY_NAMES: [&str; 3] = ["random", "first-consistent", "minimax"];
+
+/// The strategy with a name from STRATEGY_NAMES. The seed is only used by strategies which pick at
+/// random.
+pub fn new_strategy(name: &str, seed: u64) -> Option<Box<dyn GuessStrategy>> {
+    match name {
+        "random" => Some(Box::new(RandomStrategy::new(Box::new(
+            SeededRangeRng::new(seed),
+        )))),
+        "first-consistent" => Some(Box::new(FirstConsistentStrategy)),
+        "minimax" => Some(Box::new(MinimaxStrategy)),
+        _ => None,
+    }
+}
+
+/// Play a game to the end by selecting the words the strategy picks
+pub fn play_game(game_state: &mut GameState, strategy: &mut dyn GuessStrategy) -> GameOutcome {
+    let mut candidates = game_state.board().words.clone();
+    while !game_state.is_over() {
+        let board = game_state.board();
+        let unguessed_words: Vec<String> = board
+            .words
+            .iter()
+            .filter(|word| !game_state.guesses().contains(word))
+            .cloned()
+            .collect();
+        let guess = strategy.pick_guess(&unguessed_words, &candidates);
+
+        // move the cursor onto the word the same as clicking on it would
+        let word_index = board
+            .words
+            .iter()
+            .position(|word| *word == guess)
+            .expect("strategies have to guess a word from the board");
+        let chunk = SelectedChunk::at_offset(board.word_offsets[word_index], &board.pane_size);
+        game_state.handle(GameCmd::MoveTo {
+            pane_num: chunk.pane_num,
+            row_num: chunk.row_num,
+            col: chunk.col_start,
+        });
+
+        for event in game_state.handle(GameCmd::Select) {
+            if let GameEvent::EntryDenied { word, likeness } = event {
+                let known_guess = KnownGuess::new(word, likeness);
+                candidates = filter_matching_passwords(&known_guess, candidates);
+            }
+        }
+    }
+
+    game_state.outcome().unwrap()
+}
+
+/// How a strategy did over a number of games
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfPlayStats {
+    pub games: usize,
+    /// How many games were won with each number of guesses, starting from one guess
+    pub wins_by_guesses: Vec<usize>,
+    pub losses: usize,
+}
+
+impl SelfPlayStats {
+    pub fn new(attempts: usize) -> Self {
+        SelfPlayStats {
+            games: 0,
+            wins_by_guesses: vec![0; attempts],
+            losses: 0,
+        }
+    }
+
+    pub fn record(&mut self, outcome: GameOutcome, guesses: usize) {
+        self.games += 1;
+        match outcome {
+            GameOutcome::Won => self.wins_by_guesses[guesses - 1] += 1,
+            _ => self.losses += 1,
+        }
+    }
+
+    pub fn win_rate(&self) -> f32 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        (self.games - self.losses) as f32 / self.games as f32
+    }
+}
+
+/// How many boards needed each number of attempts in the worst case, going by the best guessing
+/// strategy
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SolvabilityStats {
+    /// Starting from one attempt
+    pub boards_by_worst_case: Vec<usize>,
+}
+
+impl SolvabilityStats {
+    pub fn record(&mut self, worst_case_attempts: usize) {
+        if self.boards_by_worst_case.len() < worst_case_attempts {
+            self.boards_by_worst_case.resize(worst_case_attempts, 0);
+        }
+        self.boards_by_worst_case[worst_case_attempts - 1] += 1;
+    }
+
+    /// The boards which even the best strategy can lose with this many attempts
+    pub fn unsolvable_within(&self, attempts: usize) -> usize {
+        self.boards_by_worst_case.iter().skip(attempts).sum()
+    }
+}
+
+/// Play each strategy against the same boards generated from a profile, one board for each of the
+/// games seeds counting up from seed. Returns each strategy's stats in the same order, and how
+/// solvable the boards were unless the profile has too many words to work that out in time.
+pub fn benchmark_profile(
+    profile: &DifficultyProfile,
+    strategies: &mut [Box<dyn GuessStrategy>],
+    games: usize,
+    seed: u64,
+    dict_source: &DictSource,
+) -> Result<(Vec<SelfPlayStats>, Option<SolvabilityStats>), GenerateErr> {
+    let dict_chunk =
+        EnglishDictChunk::load(dict_source, profile.word_len).map_err(GenerateErr::LoadDict)?;
+    let mut all_stats = vec![SelfPlayStats::new(profile.attempts); strategies.len()];
+    let mut solvability_stats = if profile.word_count <= MAX_WORD_COUNT_WITH_SOLVABILITY {
+        Some(SolvabilityStats::default())
+    } else {
+        None
+    };
+
+    // the seeds wrap around rather than overflowing when started near u64::MAX
+    for game_seed in (0..games as u64).map(|i| seed.wrapping_add(i)) {
+        let mut rng = SeededRangeRng::new(game_seed);
+        let (board, worst_case) = Board::generate_measured(profile, &dict_chunk, &mut rng)?;
+        if let Some(solvability_stats) = &mut solvability_stats {
+            // boards generated within solvability limits have been measured already
+            solvability_stats.record(worst_case.unwrap_or_else(|| board.worst_case_attempts()));
+        }
+        for (strategy, stats) in strategies.iter_mut().zip(all_stats.iter_mut()) {
+            let rng = Box::new(SeededRangeRng::new(game_seed));
+            let mut game_state = GameState::new(board.clone(), profile.attempts, None, rng);
+            let outcome = play_game(&mut game_state, strategy.as_mut());
+            stats.record(outcome, game_state.guesses().len());
+        }
+    }
+
+    Ok((all_stats, solvability_stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Difficulty;
+    use crate::game_state::tests::test_board;
+    use snm_rand_utils::mocks as rand_mocks;
+
+    // the game state tests' board with the password swapped for any of its words
+    fn test_game(solution: &str, attempts: usize) -> GameState {
+        let board = Board {
+            solution: String::from(solution),
+            ..test_board()
+        };
+        let rng = rand_mocks::SingleValueRangeRng::new(0);
+        GameState::new(board, attempts, None, Box::new(rng))
+    }
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| String::from(*word)).collect()
+    }
+
+    #[test]
+    fn check_strategies_pick_guesses() {
+        let all_words = words(&["xyz", "abd", "abc"]);
+        let candidates = words(&["abd", "abc"]);
+
+        assert_eq!(
+            FirstConsistentStrategy.pick_guess(&all_words, &candidates),
+            "abd"
+        );
+        // xyz can't split abd from abc but either of them can
+        assert_eq!(MinimaxStrategy.pick_guess(&all_words, &candidates), "abd");
+
+        let rng = rand_mocks::SingleValueRangeRng::new(0);
+        let mut random_strategy = RandomStrategy::new(Box::new(rng));
+        assert_eq!(random_strategy.pick_guess(&all_words, &candidates), "xyz");
+
+        for name in &STRATEGY_NAMES {
+            assert_eq!(new_strategy(name, 0).unwrap().name(), *name);
+        }
+        assert!(new_strategy("telepathy", 0).is_none());
+    }
+
+    #[test]
+    fn check_play_game() {
+        // abc rules out abd, which shares two letters with it, but not xyz
+        let mut game_state = test_game("xyz", 4);
+        assert_eq!(
+            play_game(&mut game_state, &mut FirstConsistentStrategy),
+            GameOutcome::Won
+        );
+        assert_eq!(game_state.guesses(), ["abc", "xyz"]);
+
+        let mut game_state = test_game("abd", 4);
+        assert_eq!(
+            play_game(&mut game_state, &mut MinimaxStrategy),
+            GameOutcome::Won
+        );
+        assert_eq!(game_state.guesses(), ["abc", "abd"]);
+
+        // always picking the first word left tries each word in turn
+        let rng = rand_mocks::SingleValueRangeRng::new(0);
+        let mut random_strategy = RandomStrategy::new(Box::new(rng));
+        let mut game_state = test_game("xyz", 2);
+        assert_eq!(
+            play_game(&mut game_state, &mut random_strategy),
+            GameOutcome::Lost
+        );
+        assert_eq!(game_state.guesses(), ["abc", "abd"]);
+    }
+
+    #[test]
+    fn check_self_play_stats() {
+        let mut stats = SelfPlayStats::new(4);
+        assert_eq!(stats.win_rate(), 0.0);
+
+        stats.record(GameOutcome::Won, 1);
+        stats.record(GameOutcome::Won, 3);
+        stats.record(GameOutcome::Won, 3);
+        stats.record(GameOutcome::Lost, 4);
+        assert_eq!(stats.games, 4);
+        assert_eq!(stats.wins_by_guesses, [1, 0, 2, 0]);
+        assert_eq!(stats.losses, 1);
+        assert_eq!(stats.win_rate(), 0.75);
+    }
+
+    #[test]
+    fn check_solvability_stats() {
+        let mut stats = SolvabilityStats::default();
+        assert_eq!(stats.unsolvable_within(4), 0);
+
+        for worst_case_attempts in &[2, 3, 2, 6, 5] {
+            stats.record(*worst_case_attempts);
+        }
+        assert_eq!(stats.boards_by_worst_case, [0, 2, 1, 0, 1, 1]);
+        assert_eq!(stats.unsolvable_within(4), 2);
+        assert_eq!(stats.unsolvable_within(6), 0);
+    }
+
+    #[test]
+    fn check_benchmark_only_measures_solvability_when_it_can() {
+        let mut strategies: Vec<Box<dyn GuessStrategy>> = vec![Box::new(MinimaxStrategy)];
+        let mut benchmark = |settings: &str| {
+            let mut profile = DifficultyProfile::preset(Difficulty::Average);
+            profile.apply_settings(settings).unwrap();
+            benchmark_profile(&profile, &mut strategies, 3, 0, &DictSource::Builtin).unwrap()
+        };
+
+        let (all_stats, solvability_stats) = benchmark("min_solvability 4");
+        assert_eq!(all_stats[0].games, 3);
+        assert_eq!(all_stats[0].losses, 0);
+        let boards_by_worst_case = solvability_stats.unwrap().boards_by_worst_case;
+        assert_eq!(boards_by_worst_case.iter().sum::<usize>(), 3);
+        assert!(boards_by_worst_case.len() <= 4);
+
+        // too many words to build every board's decision tree
+        let (all_stats, solvability_stats) = benchmark("pane 32x32\nwords 40");
+        assert_eq!(all_stats[0].games, 3);
+        assert!(solvability_stats.is_none());
+    }
+}
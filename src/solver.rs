@@ -1,16 +1,81 @@
-use crate::dict::dict::EnglishDictChunk;
+use crate::dict::dict::{DictSource, EnglishDictChunk};
 use crate::utils::str_utils::matching_char_count_ignore_case;
-use crate::utils::tui::{ascii_keycodes, Rect};
 
 #[derive(Debug, PartialEq, Eq)]
-enum InputValidationErr {
+pub enum InputValidationErr {
     InputEmpty,
     InvalidPasswordLengthFound,
     PasswordNotFoundInEnglishDict,
+    // the dictionary for the password length couldn't be loaded (stores the io error text)
+    EnglishDictUnavailable(String),
 }
 
-fn validate_input_passwords(pwds: Vec<String>) -> Result<Vec<String>, InputValidationErr> {
-    if pwds.len() == 0 {
+impl std::fmt::Display for InputValidationErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            InputValidationErr::InputEmpty => write!(f, "the password list is empty"),
+            InputValidationErr::InvalidPasswordLengthFound => {
+                write!(f, "the passwords in the list are not all the same length")
+            }
+            InputValidationErr::PasswordNotFoundInEnglishDict => {
+                write!(
+                    f,
+                    "the password list contains a word missing from the dictionary"
+                )
+            }
+            InputValidationErr::EnglishDictUnavailable(err) => {
+                write!(f, "the dictionary couldn't be loaded ({})", err)
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SolverError {
+    ReadPasswordFile(String, std::io::Error),
+    InvalidPasswordList(InputValidationErr),
+    OddGuessArgCount,
+    InvalidGuessCount(String),
+    GuessCountTooLarge { word: String, char_count: usize },
+    UnknownGuessWord(String),
+    // no password matches every guess (stores a description of the conflicting guesses)
+    ContradictoryGuesses(String),
+}
+
+impl std::fmt::Display for SolverError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SolverError::ReadPasswordFile(file, err) => {
+                write!(f, "Couldn't read password file '{}': {}", file, err)
+            }
+            SolverError::InvalidPasswordList(err) => write!(f, "Invalid password list: {}", err),
+            SolverError::OddGuessArgCount => write!(
+                f,
+                "Each guess needs a matching char count (expected: guess matching_char_count)"
+            ),
+            SolverError::InvalidGuessCount(count) => {
+                write!(f, "'{}' is not a valid matching char count", count)
+            }
+            SolverError::GuessCountTooLarge { word, char_count } => write!(
+                f,
+                "'{}' can't have {} matching chars; it only has {} chars",
+                word,
+                char_count,
+                word.len()
+            ),
+            SolverError::UnknownGuessWord(word) => {
+                write!(f, "'{}' was not found in the password list", word)
+            }
+            SolverError::ContradictoryGuesses(description) => write!(f, "{}", description),
+        }
+    }
+}
+
+pub fn validate_input_passwords(
+    pwds: Vec<String>,
+    dict_source: &DictSource,
+) -> Result<Vec<String>, InputValidationErr> {
+    if pwds.is_empty() {
         return Err(InputValidationErr::InputEmpty);
     }
 
@@ -20,9 +85,10 @@ fn validate_input_passwords(pwds: Vec<String>) -> Result<Vec<String>, InputValid
         return Err(InputValidationErr::InvalidPasswordLengthFound);
     }
 
-    let dict = EnglishDictChunk::load(required_len);
+    let dict = EnglishDictChunk::load(dict_source, required_len)
+        .map_err(|e| InputValidationErr::EnglishDictUnavailable(e.to_string()))?;
 
-    let all_valid_words = pwds.iter().all(|p| dict.is_word(&p));
+    let all_valid_words = pwds.iter().all(|p| dict.is_word(p));
     if !all_valid_words {
         return Err(InputValidationErr::PasswordNotFoundInEnglishDict);
     }
@@ -30,14 +96,15 @@ fn validate_input_passwords(pwds: Vec<String>) -> Result<Vec<String>, InputValid
     Ok(pwds)
 }
 
-#[derive(Debug)]
-struct KnownGuess {
+/// A guess which has been made and the likeness the game reported back for it
+#[derive(Debug, Clone)]
+pub struct KnownGuess {
     word: String,
     char_count: usize,
 }
 
 impl KnownGuess {
-    fn new<S>(word: S, char_count: usize) -> Self
+    pub fn new<S>(word: S, char_count: usize) -> Self
     where
         S: AsRef<str>,
     {
@@ -46,9 +113,18 @@ impl KnownGuess {
             char_count,
         }
     }
+
+    pub fn word(&self) -> &str {
+        &self.word
+    }
+
+    pub fn char_count(&self) -> usize {
+        self.char_count
+    }
 }
 
-fn filter_matching_passwords<S>(guess: &KnownGuess, mut passwords: Vec<S>) -> Vec<S>
+/// Keep only the passwords which would have given the guess its likeness
+pub fn filter_matching_passwords<S>(guess: &KnownGuess, mut passwords: Vec<S>) -> Vec<S>
 where
     S: AsRef<str>,
 {
@@ -61,200 +137,253 @@ where
     passwords
 }
 
-pub fn solver(password_file: &str, guess_args: &[String], window: &pancurses::Window) {
-    let mut input_passwords = {
-        let pwds: Vec<String> = snm_simple_file::read_lines(&password_file).collect();
-        match validate_input_passwords(pwds) {
-            Ok(validated_pwds) => validated_pwds,
-            Err(e) => panic!("Input failed validation: {:?}", e),
-        }
-    };
-
-    let known_guesses = {
-        let mut known_guesses = Vec::new();
-        for guess_slice in guess_args.chunks(2) {
-            let guess_word = &guess_slice[0];
-            let guess_char_count = &guess_slice[1];
-            known_guesses.push(KnownGuess::new(
-                guess_word,
-                guess_char_count.parse().unwrap(),
+fn any_password_matches_all<S>(known_guesses: &[&KnownGuess], passwords: &[S]) -> bool
+where
+    S: AsRef<str>,
+{
+    passwords.iter().any(|password| {
+        known_guesses.iter().all(|guess| {
+            matching_char_count_ignore_case(password.as_ref(), &guess.word) == guess.char_count
+        })
+    })
+}
+
+/// Explains why no password matches every known guess
+#[derive(Debug, PartialEq, Eq)]
+pub struct GuessContradiction {
+    /// the indices of the smallest set of known guesses which no password can satisfy together
+    pub conflicting_guesses: Vec<usize>,
+    /// the index of the guess most likely entered wrong and the char count which makes every guess
+    /// consistent again. None if no single corrected char count would fix the guesses.
+    pub likely_typo: Option<(usize, usize)>,
+}
+
+impl GuessContradiction {
+    pub fn describe(&self, known_guesses: &[KnownGuess]) -> String {
+        let conflicting_guess_text: Vec<String> = self
+            .conflicting_guesses
+            .iter()
+            .map(|i| {
+                format!(
+                    "{} {}",
+                    known_guesses[*i].word, known_guesses[*i].char_count
+                )
+            })
+            .collect();
+
+        let mut description = format!(
+            "No password matches every guess. Conflicting guesses: {}.",
+            conflicting_guess_text.join(", ")
+        );
+        if let Some((typo_index, corrected_char_count)) = self.likely_typo {
+            let typo_guess = &known_guesses[typo_index];
+            description.push_str(&format!(
+                " Likely typo: {} {} (did you mean {}?)",
+                typo_guess.word, typo_guess.char_count, corrected_char_count
             ));
         }
-        known_guesses
-    };
+        description
+    }
+}
 
-    for guess in &known_guesses {
-        if !input_passwords.contains(&guess.word) {
-            panic!("{} was not found in password list!", guess.word);
+// Advance `indices` to the next k-combination of 0..n in lexicographic order. Returns false once
+// every combination has been visited.
+fn next_combination(indices: &mut [usize], n: usize) -> bool {
+    let k = indices.len();
+    for i in (0..k).rev() {
+        if indices[i] < n - k + i {
+            indices[i] += 1;
+            for j in (i + 1)..k {
+                indices[j] = indices[j - 1] + 1;
+            }
+            return true;
         }
     }
+    false
+}
 
-    for known_guess in &known_guesses {
-        input_passwords = filter_matching_passwords(&known_guess, input_passwords);
+/// Figure out which known guesses conflict with each other when no password satisfies all of them.
+pub fn find_contradiction<S>(
+    known_guesses: &[KnownGuess],
+    passwords: &[S],
+) -> Option<GuessContradiction>
+where
+    S: AsRef<str>,
+{
+    let all_guesses: Vec<&KnownGuess> = known_guesses.iter().collect();
+    if known_guesses.is_empty() || any_password_matches_all(&all_guesses, passwords) {
+        return None;
     }
 
-    let mut menu_cursor: i32 = 0;
-    let cursor_prefix = "> ";
-    let cursor_prefix_len = cursor_prefix.len() as i32;
-    let word_column_width = input_passwords.iter().map(|p| p.len()).max().unwrap() as i32;
-    let padding_width = 4;
-    let char_count_column_width = 2; // 00
-
-    let menu_rect = {
-        let menu_width =
-            cursor_prefix_len + word_column_width + padding_width + char_count_column_width;
-        let menu_height = input_passwords.len() as i32;
-
-        Rect {
-            // center the menu options horizontally
-            left: (window.get_max_x() - menu_width) / 2,
-            // center the menu options vertically
-            top: (window.get_max_y() - menu_height) / 2,
-            width: menu_width,
-            height: menu_height,
-        }
-    };
-
-    let mut refresh_filtered_passwords = true;
-    let mut clear_on_next_number_input = true;
-    let mut number_input_buffers = vec![String::new(); input_passwords.len()];
-    let mut filtered_input_passwords = Vec::new();
-
-    loop {
-        if refresh_filtered_passwords {
-            refresh_filtered_passwords = false;
-
-            fn xform_password_tuple_to_known_guess(
-                (pwd, matching_char_count_string): (&String, &String),
-            ) -> KnownGuess {
-                KnownGuess {
-                    word: pwd.clone(),
-                    char_count: matching_char_count_string.parse().unwrap(),
-                }
+    // check the smallest sets of guesses first so the first conflicting set found is minimal
+    let mut conflicting_guesses = Vec::new();
+    'search: for set_size in 1..=known_guesses.len() {
+        let mut indices: Vec<usize> = (0..set_size).collect();
+        loop {
+            let guess_set: Vec<&KnownGuess> = indices.iter().map(|i| &known_guesses[*i]).collect();
+            if !any_password_matches_all(&guess_set, passwords) {
+                conflicting_guesses = indices;
+                break 'search;
             }
 
-            fn require_non_empty_number_buffers(
-                (_, matching_char_count_string): &(&String, &String),
-            ) -> bool {
-                !matching_char_count_string.is_empty()
+            if !next_combination(&mut indices, known_guesses.len()) {
+                break;
             }
+        }
+    }
 
-            let known_guesses = input_passwords
-                .iter()
-                .zip(number_input_buffers.iter())
-                .filter(require_non_empty_number_buffers)
-                .map(xform_password_tuple_to_known_guess);
-
-            filtered_input_passwords = input_passwords.clone();
-            for guess in known_guesses {
-                filtered_input_passwords =
-                    filter_matching_passwords(&guess, filtered_input_passwords);
+    // A typo is most likely a slip to a nearby number, so look for the conflicting guess which
+    // needs the smallest change to its char count to make every guess consistent again.
+    let mut likely_typo: Option<(usize, usize)> = None;
+    let mut likely_typo_distance = usize::MAX;
+    for guess_index in &conflicting_guesses {
+        let guess = &known_guesses[*guess_index];
+        for corrected_char_count in 0..=guess.word.len() {
+            let distance = corrected_char_count.abs_diff(guess.char_count);
+            if distance == 0 || distance >= likely_typo_distance {
+                continue;
             }
-        }
 
-        // Input handling
-        // TODO: I think this input system might need some refactoring to share with the start menu
-        if let Some(pancurses::Input::Character(ch)) = window.getch() {
-            match ch {
-                // check for movement inputs
-                'w' => {
-                    menu_cursor = std::cmp::max(0, menu_cursor - 1);
-                    refresh_filtered_passwords = true;
-                    clear_on_next_number_input = true;
-                }
-                's' => {
-                    menu_cursor = std::cmp::min(input_passwords.len() as i32, menu_cursor + 1);
-                    refresh_filtered_passwords = true;
-                    clear_on_next_number_input = true;
-                }
-                ascii_keycodes::ENTER => {
-                    if menu_cursor == input_passwords.len() as i32 {
-                        break;
+            let corrected_guess = KnownGuess::new(&guess.word, corrected_char_count);
+            let corrected_guesses: Vec<&KnownGuess> = known_guesses
+                .iter()
+                .enumerate()
+                .map(|(i, g)| {
+                    if i == *guess_index {
+                        &corrected_guess
                     } else {
-                        refresh_filtered_passwords = true;
-                        clear_on_next_number_input = true;
+                        g
                     }
-                }
-                '0' | '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' | '9' => {
-                    let menu_idx = menu_cursor as usize;
-                    if menu_idx < input_passwords.len() {
-                        let number_input_buffer = &mut number_input_buffers[menu_idx];
-                        if clear_on_next_number_input {
-                            clear_on_next_number_input = false;
-                            *number_input_buffer = String::new();
-                        }
-
-                        if number_input_buffer.len() < 2 {
-                            number_input_buffer.push(ch);
-                        }
-                    }
-                }
-                ascii_keycodes::BKSP | ascii_keycodes::DEL => {
-                    let menu_idx = menu_cursor as usize;
-                    if menu_idx < input_passwords.len() {
-                        number_input_buffers[menu_idx].pop();
-                    }
-                }
-                ascii_keycodes::ESC => break,
-                _ => (),
+                })
+                .collect();
+            if any_password_matches_all(&corrected_guesses, passwords) {
+                likely_typo = Some((*guess_index, corrected_char_count));
+                likely_typo_distance = distance;
             }
-        };
+        }
+    }
 
-        window.erase();
+    Some(GuessContradiction {
+        conflicting_guesses,
+        likely_typo,
+    })
+}
 
-        for (i, pwd) in input_passwords.iter().enumerate() {
-            if !filtered_input_passwords.contains(pwd) {
-                window.attron(pancurses::A_DIM);
-            }
-            let row = i as i32 + menu_rect.top;
-            let col_offset = menu_rect.left + cursor_prefix_len;
-            window.mvaddstr(row, col_offset, pwd);
-
-            window.attron(pancurses::A_UNDERLINE);
-            window.mvaddstr(
-                row,
-                col_offset + menu_rect.width - char_count_column_width,
-                format!("{:>2}", number_input_buffers[i]),
-            );
-            window.attroff(pancurses::A_UNDERLINE);
-            window.attroff(pancurses::A_DIM);
-        }
+/// How well a guess narrows down the remaining candidates. Each candidate would cause the game to
+/// report a different likeness count for the guess, so the guess partitions the candidates by that
+/// count. Smaller partitions are better. Scores compare by worst case first and expected case second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GuessScore {
+    /// The most candidates that could be left after making the guess
+    pub worst_case_remaining: usize,
+    // the sum of the squared partition sizes. Dividing by the number of candidates gives the
+    // expected number of candidates left after making the guess.
+    expected_remaining_weight: usize,
+}
 
-        let back_button_row = menu_rect.top + (input_passwords.len() + 1) as i32;
-        let back_button_text = "[ Back ]";
-        window.mvaddstr(
-            back_button_row,
-            menu_rect.left + cursor_prefix_len,
-            back_button_text,
-        );
+impl GuessScore {
+    pub fn expected_remaining(&self, num_candidates: usize) -> f32 {
+        self.expected_remaining_weight as f32 / num_candidates as f32
+    }
+}
 
-        if (menu_cursor as usize) < input_passwords.len() {
-            let cursor_row = menu_rect.top + menu_cursor;
-            window.mvaddstr(cursor_row, menu_rect.left, cursor_prefix);
-            window.mvchgat(
-                cursor_row,
-                menu_rect.left + cursor_prefix_len + menu_rect.width - char_count_column_width,
-                char_count_column_width,
-                pancurses::A_BLINK,
-                0,
-            );
-        } else {
-            assert_eq!(menu_cursor as usize, input_passwords.len());
-            window.mvchgat(
-                back_button_row,
-                menu_rect.left + cursor_prefix_len,
-                back_button_text.len() as i32,
-                pancurses::A_BLINK,
-                0,
-            );
+pub fn score_guess<S>(guess: &str, candidates: &[S]) -> GuessScore
+where
+    S: AsRef<str>,
+{
+    let mut partition_sizes = vec![0; guess.len() + 1];
+    for candidate in candidates {
+        partition_sizes[matching_char_count_ignore_case(candidate.as_ref(), guess)] += 1;
+    }
+
+    // a full match means the guess was the password so there is nothing left to solve
+    partition_sizes[guess.len()] = 0;
+
+    GuessScore {
+        worst_case_remaining: *partition_sizes.iter().max().unwrap(),
+        expected_remaining_weight: partition_sizes.iter().map(|size| size * size).sum(),
+    }
+}
+
+/// Pick the candidate which best narrows down the rest of the candidates. Ties go to the earliest
+/// candidate in the list.
+pub fn recommend_guess<S>(candidates: &[S]) -> Option<(usize, GuessScore)>
+where
+    S: AsRef<str>,
+{
+    candidates
+        .iter()
+        .enumerate()
+        .map(|(i, candidate)| (i, score_guess(candidate.as_ref(), candidates)))
+        .min_by_key(|(_, score)| *score)
+}
+
+pub fn load_input_passwords(
+    password_file: &str,
+    dict_source: &DictSource,
+) -> Result<Vec<String>, SolverError> {
+    let pwds: Vec<String> = std::fs::read_to_string(password_file)
+        .map_err(|e| SolverError::ReadPasswordFile(String::from(password_file), e))?
+        .lines()
+        .map(|line| String::from(line.trim()))
+        .filter(|line| !line.is_empty())
+        .collect();
+    validate_input_passwords(pwds, dict_source).map_err(SolverError::InvalidPasswordList)
+}
+
+pub fn parse_known_guesses(
+    guess_args: &[String],
+    input_passwords: &[String],
+) -> Result<Vec<KnownGuess>, SolverError> {
+    if guess_args.len() % 2 == 1 {
+        return Err(SolverError::OddGuessArgCount);
+    }
+
+    let mut known_guesses = Vec::new();
+    for guess_slice in guess_args.chunks(2) {
+        let guess_word = &guess_slice[0];
+        let guess_char_count = &guess_slice[1];
+
+        if !input_passwords.contains(guess_word) {
+            return Err(SolverError::UnknownGuessWord(guess_word.clone()));
         }
 
-        window.refresh();
+        let char_count = guess_char_count
+            .parse()
+            .map_err(|_| SolverError::InvalidGuessCount(guess_char_count.clone()))?;
+        if char_count > guess_word.len() {
+            return Err(SolverError::GuessCountTooLarge {
+                word: guess_word.clone(),
+                char_count,
+            });
+        }
 
-        // No need to waste cycles doing nothing but rendering over and over.
-        // Yield the processor until the next frame.
-        std::thread::sleep(std::time::Duration::from_millis(33));
+        known_guesses.push(KnownGuess::new(guess_word, char_count));
     }
+    Ok(known_guesses)
+}
+
+/// Load the password list and narrow it down to the passwords matching the guesses from the cmdline
+pub fn load_candidate_passwords(
+    password_file: &str,
+    guess_args: &[String],
+    dict_source: &DictSource,
+) -> Result<Vec<String>, SolverError> {
+    let input_passwords = load_input_passwords(password_file, dict_source)?;
+    let known_guesses = parse_known_guesses(guess_args, &input_passwords)?;
+
+    if let Some(contradiction) = find_contradiction(&known_guesses, &input_passwords) {
+        return Err(SolverError::ContradictoryGuesses(
+            contradiction.describe(&known_guesses),
+        ));
+    }
+
+    let mut candidate_passwords = input_passwords;
+    for known_guess in &known_guesses {
+        candidate_passwords = filter_matching_passwords(known_guess, candidate_passwords);
+    }
+
+    Ok(candidate_passwords)
 }
 
 #[cfg(test)]
@@ -264,7 +393,7 @@ mod tests {
     #[test]
     fn check_input_validation_empty_input() {
         assert_eq!(
-            validate_input_passwords(vec![]).unwrap_err(),
+            validate_input_passwords(vec![], &DictSource::Builtin).unwrap_err(),
             InputValidationErr::InputEmpty,
         );
     }
@@ -272,11 +401,14 @@ mod tests {
     #[test]
     fn check_input_validation_unequal_len_input() {
         assert_eq!(
-            validate_input_passwords(vec![
-                String::from("apple"),
-                String::from("bale"),
-                String::from("grape")
-            ])
+            validate_input_passwords(
+                vec![
+                    String::from("apple"),
+                    String::from("bale"),
+                    String::from("grape")
+                ],
+                &DictSource::Builtin
+            )
             .unwrap_err(),
             InputValidationErr::InvalidPasswordLengthFound,
         );
@@ -297,12 +429,12 @@ mod tests {
         ];
 
         assert_eq!(
-            validate_input_passwords(input_with_valid_words.clone()).unwrap(),
+            validate_input_passwords(input_with_valid_words.clone(), &DictSource::Builtin).unwrap(),
             input_with_valid_words,
         );
 
         assert_eq!(
-            validate_input_passwords(input_with_invalid_words).unwrap_err(),
+            validate_input_passwords(input_with_invalid_words, &DictSource::Builtin).unwrap_err(),
             InputValidationErr::PasswordNotFoundInEnglishDict,
         );
     }
@@ -315,4 +447,114 @@ mod tests {
 
         assert_eq!(filter_matching_passwords(&guess, pwd_start), pwd_remaining);
     }
+
+    #[test]
+    fn check_parse_known_guesses() {
+        let input_passwords = vec![String::from("apple"), String::from("grape")];
+        let args =
+            |args: &[&str]| -> Vec<String> { args.iter().map(|a| String::from(*a)).collect() };
+
+        let known_guesses = parse_known_guesses(&args(&["apple", "2"]), &input_passwords).unwrap();
+        assert_eq!(known_guesses.len(), 1);
+        assert_eq!(known_guesses[0].word, "apple");
+        assert_eq!(known_guesses[0].char_count, 2);
+
+        assert!(matches!(
+            parse_known_guesses(&args(&["apple"]), &input_passwords),
+            Err(SolverError::OddGuessArgCount)
+        ));
+        assert!(matches!(
+            parse_known_guesses(&args(&["apple", "two"]), &input_passwords),
+            Err(SolverError::InvalidGuessCount(_))
+        ));
+        assert!(matches!(
+            parse_known_guesses(&args(&["apple", "6"]), &input_passwords),
+            Err(SolverError::GuessCountTooLarge { .. })
+        ));
+        assert!(matches!(
+            parse_known_guesses(&args(&["mango", "2"]), &input_passwords),
+            Err(SolverError::UnknownGuessWord(_))
+        ));
+    }
+
+    #[test]
+    fn check_score_guess() {
+        let candidates = ["abc", "abd", "xyz"];
+
+        // abc => abc (solved), abd (2), xyz (0)
+        assert_eq!(
+            score_guess("abc", &candidates),
+            GuessScore {
+                worst_case_remaining: 1,
+                expected_remaining_weight: 2,
+            }
+        );
+
+        // xyz => abc (0), abd (0), xyz (solved)
+        assert_eq!(
+            score_guess("xyz", &candidates),
+            GuessScore {
+                worst_case_remaining: 2,
+                expected_remaining_weight: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn check_recommend_guess() {
+        let candidates = ["xyz", "abd", "abc"];
+        let (recommended_index, score) = recommend_guess(&candidates).unwrap();
+
+        // abd and abc are equally good so the first of them is picked
+        assert_eq!(recommended_index, 1);
+        assert_eq!(score.worst_case_remaining, 1);
+
+        let no_candidates: [&str; 0] = [];
+        assert_eq!(recommend_guess(&no_candidates), None);
+    }
+
+    #[test]
+    fn check_find_contradiction() {
+        let passwords = ["abc", "abd", "xyz"];
+
+        // abc 2 => abd, abd 3 => abd
+        let consistent_guesses = [KnownGuess::new("abc", 2), KnownGuess::new("abd", 3)];
+        assert_eq!(find_contradiction(&consistent_guesses, &passwords), None);
+
+        // abd 1 can't match any password on its own so it's the only conflicting guess. Only 3
+        // makes it consistent with abc 2 again.
+        let single_conflict_guesses = [KnownGuess::new("abc", 2), KnownGuess::new("abd", 1)];
+        assert_eq!(
+            find_contradiction(&single_conflict_guesses, &passwords),
+            Some(GuessContradiction {
+                conflicting_guesses: vec![1],
+                likely_typo: Some((1, 3)),
+            })
+        );
+
+        // abc 2 => abd, xyz 3 => xyz. Each works alone but not together. abc 0 is the closest fix.
+        let paired_conflict_guesses = [KnownGuess::new("abc", 2), KnownGuess::new("xyz", 3)];
+        assert_eq!(
+            find_contradiction(&paired_conflict_guesses, &passwords),
+            Some(GuessContradiction {
+                conflicting_guesses: vec![0, 1],
+                likely_typo: Some((0, 0)),
+            })
+        );
+    }
+
+    #[test]
+    fn check_describe_contradiction() {
+        let known_guesses = [KnownGuess::new("abc", 2), KnownGuess::new("abd", 1)];
+        let contradiction = GuessContradiction {
+            conflicting_guesses: vec![1],
+            likely_typo: Some((1, 3)),
+        };
+
+        assert_eq!(
+            contradiction.describe(&known_guesses),
+            "No password matches every guess. Conflicting guesses: abd 1. \
+             Likely typo: abd 1 (did you mean 3?)"
+        );
+    }
 }
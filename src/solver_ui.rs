@@ -0,0 +1,708 @@
+use crate::input::{InputCmd, KeyBindings};
+use crate::tui::{render_too_small_message, Attrs, Key, MouseAction, Rect, Terminal};
+use fonv_cracker::dict::dict::{DictSource, EnglishDictChunk};
+use fonv_cracker::game_state::Movement;
+use fonv_cracker::solver::{
+    filter_matching_passwords, find_contradiction, recommend_guess, GuessContradiction, KnownGuess,
+};
+use fonv_cracker::utils::str_utils::wrap_text;
+use std::collections::HashMap;
+
+// Problems with a word typed into the solver which keep it out of the password list
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum WordIssue {
+    WrongLength(usize),
+    NotInEnglishDict,
+    EnglishDictUnavailable,
+    Duplicate,
+}
+
+impl WordIssue {
+    fn describe(&self) -> String {
+        match self {
+            WordIssue::WrongLength(required_len) => {
+                format!("Passwords must all be {} letters long", required_len)
+            }
+            WordIssue::NotInEnglishDict => String::from("Not found in the dictionary"),
+            WordIssue::EnglishDictUnavailable => {
+                String::from("No dictionary available for this word length")
+            }
+            WordIssue::Duplicate => String::from("Already in the password list"),
+        }
+    }
+}
+
+// Check each word against the same rules as validate_input_passwords. The most common word length
+// is treated as the required one so a single mistyped word doesn't flag every other word. Empty
+// words are still being typed so they never have an issue.
+fn find_word_issues<S>(
+    words: &[S],
+    is_word: &mut dyn FnMut(&str) -> Option<bool>,
+) -> Vec<Option<WordIssue>>
+where
+    S: AsRef<str>,
+{
+    let required_len = {
+        let mut len_counts: Vec<(usize, usize)> = Vec::new();
+        for word in words.iter().map(|w| w.as_ref()).filter(|w| !w.is_empty()) {
+            match len_counts.iter_mut().find(|(len, _)| *len == word.len()) {
+                Some((_, count)) => *count += 1,
+                None => len_counts.push((word.len(), 1)),
+            }
+        }
+
+        // max_by_key picks the last max so walk backwards to have ties go to the earliest length
+        len_counts
+            .iter()
+            .rev()
+            .max_by_key(|(_, count)| *count)
+            .map(|(len, _)| *len)
+    };
+
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| {
+            let word = word.as_ref();
+            if word.is_empty() {
+                return None;
+            }
+
+            if Some(word.len()) != required_len {
+                return Some(WordIssue::WrongLength(required_len.unwrap()));
+            }
+
+            let is_duplicate = words[..i]
+                .iter()
+                .any(|w| w.as_ref().eq_ignore_ascii_case(word));
+            if is_duplicate {
+                return Some(WordIssue::Duplicate);
+            }
+
+            match is_word(word) {
+                Some(true) => None,
+                Some(false) => Some(WordIssue::NotInEnglishDict),
+                None => Some(WordIssue::EnglishDictUnavailable),
+            }
+        })
+        .collect()
+}
+
+// Dictionary chunks loaded on demand as words of new lengths get typed into the solver
+struct EnglishDictCache<'a> {
+    dict_source: &'a DictSource,
+    chunks: HashMap<usize, Option<EnglishDictChunk>>,
+}
+
+impl<'a> EnglishDictCache<'a> {
+    fn new(dict_source: &'a DictSource) -> Self {
+        EnglishDictCache {
+            dict_source,
+            chunks: HashMap::new(),
+        }
+    }
+
+    // None if there's no dictionary for the word's length
+    fn is_word(&mut self, word: &str) -> Option<bool> {
+        let dict_source = self.dict_source;
+        self.chunks
+            .entry(word.len())
+            .or_insert_with(|| EnglishDictChunk::load(dict_source, word.len()).ok())
+            .as_ref()
+            .map(|dict| dict.is_word(&word.to_ascii_lowercase()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditColumn {
+    Word,
+    CharCount,
+}
+
+struct SolverRow {
+    word: String,
+    char_count_input: String,
+    issue: Option<WordIssue>,
+}
+
+impl SolverRow {
+    fn new(word: String) -> Self {
+        SolverRow {
+            word,
+            char_count_input: String::new(),
+            issue: None,
+        }
+    }
+}
+
+// Drop any rows left empty except the one under the cursor, which may still be getting typed in.
+// Returns the cursor position adjusted for the removed rows.
+fn prune_empty_rows(rows: &mut Vec<SolverRow>, cursor: usize) -> usize {
+    let mut pruned_cursor = cursor;
+    for i in (0..rows.len()).rev() {
+        if i != cursor && rows[i].word.is_empty() {
+            rows.remove(i);
+            if i < pruned_cursor {
+                pruned_cursor -= 1;
+            }
+        }
+    }
+    pruned_cursor
+}
+
+// Which row of the list is at a spot on the screen. The back button sits a row below the list and
+// counts as the row after the last one.
+fn solver_row_at(list_rect: &Rect, y: i32, x: i32) -> Option<usize> {
+    let back_button_rect = Rect {
+        top: list_rect.bottom() + 1,
+        height: 1,
+        ..*list_rect
+    };
+    if list_rect.contains(y, x) {
+        Some((y - list_rect.top) as usize)
+    } else if back_button_rect.contains(y, x) {
+        Some(list_rect.height as usize)
+    } else {
+        None
+    }
+}
+
+pub fn solver(
+    initial_passwords: Vec<String>,
+    initial_guesses: Vec<KnownGuess>,
+    dict_source: &DictSource,
+    key_bindings: &KeyBindings,
+    window: &dyn Terminal,
+) {
+    let cursor_prefix = "> ";
+    let cursor_prefix_len = cursor_prefix.len() as i32;
+    let padding_width = 4;
+    let char_count_column_width = 2; // 00
+    let issue_marker_column_width = 2; // " !"
+    const MIN_WORD_COLUMN_WIDTH: i32 = 12;
+    const HELP_TEXT: &str = "TAB: edit words/counts  DEL: remove word  ESC: back";
+
+    let mut rows: Vec<SolverRow> = initial_passwords.into_iter().map(SolverRow::new).collect();
+    for guess in initial_guesses {
+        if let Some(row) = rows.iter_mut().find(|row| row.word == guess.word()) {
+            row.char_count_input = guess.char_count().to_string();
+        }
+    }
+
+    // With no passwords to start from, jump straight into typing them in
+    let mut edit_column = EditColumn::CharCount;
+    if rows.is_empty() {
+        rows.push(SolverRow::new(String::new()));
+        edit_column = EditColumn::Word;
+    }
+
+    let mut dict_cache = EnglishDictCache::new(dict_source);
+    let mut menu_cursor: usize = 0;
+    let mut refresh_filtered_passwords = true;
+    let mut clear_on_next_number_input = true;
+    let mut filtered_input_passwords = Vec::new();
+    let mut recommended_guess = None;
+    let mut guess_contradiction = None;
+    // where the list was drawn last frame, along with the char count column, for the mouse
+    let mut list_layout: Option<(Rect, i32)> = None;
+
+    loop {
+        if refresh_filtered_passwords {
+            refresh_filtered_passwords = false;
+
+            let word_issues = {
+                let words: Vec<&str> = rows.iter().map(|row| row.word.as_str()).collect();
+                find_word_issues(&words, &mut |word| dict_cache.is_word(word))
+            };
+            for (row, issue) in rows.iter_mut().zip(word_issues) {
+                row.issue = issue;
+            }
+
+            // only solve with the words that passed validation
+            let valid_rows: Vec<usize> = (0..rows.len())
+                .filter(|i| !rows[*i].word.is_empty() && rows[*i].issue.is_none())
+                .collect();
+            let input_passwords: Vec<String> =
+                valid_rows.iter().map(|i| rows[*i].word.clone()).collect();
+
+            // remember which row each known guess came from so conflicts can be shown on that row
+            let (known_guess_rows, known_guesses): (Vec<usize>, Vec<KnownGuess>) = valid_rows
+                .iter()
+                .filter(|i| !rows[**i].char_count_input.is_empty())
+                .map(|i| {
+                    let row = &rows[*i];
+                    (
+                        *i,
+                        KnownGuess::new(&row.word, row.char_count_input.parse().unwrap()),
+                    )
+                })
+                .unzip();
+
+            filtered_input_passwords = input_passwords.clone();
+            for guess in &known_guesses {
+                filtered_input_passwords =
+                    filter_matching_passwords(guess, filtered_input_passwords);
+            }
+
+            recommended_guess = recommend_guess(&filtered_input_passwords)
+                .map(|(i, score)| (filtered_input_passwords[i].clone(), score));
+
+            guess_contradiction =
+                find_contradiction(&known_guesses, &input_passwords).map(|contradiction| {
+                    let description = contradiction.describe(&known_guesses);
+                    let contradiction_by_row = GuessContradiction {
+                        conflicting_guesses: contradiction
+                            .conflicting_guesses
+                            .iter()
+                            .map(|i| known_guess_rows[*i])
+                            .collect(),
+                        likely_typo: contradiction
+                            .likely_typo
+                            .map(|(i, char_count)| (known_guess_rows[i], char_count)),
+                    };
+                    (contradiction_by_row, description)
+                });
+        }
+
+        // Input handling
+        let back_button_index = rows.len();
+        let move_up = |cursor: usize| cursor.saturating_sub(1);
+        let move_down = |cursor: usize| std::cmp::min(back_button_index, cursor + 1);
+        let mut moved_cursor = None;
+
+        // Keys being typed into the column being edited don't trigger the commands bound to them
+        let key = window.poll_key();
+        let is_typed_key = match (edit_column, key) {
+            (EditColumn::Word, Some(Key::Char(ch))) => {
+                ch.is_ascii_alphabetic() || ch == ' ' || ch == ','
+            }
+            (EditColumn::CharCount, Some(Key::Char(ch))) => ch.is_ascii_digit(),
+            _ => false,
+        };
+        let input_cmd = match key {
+            Some(key) if !is_typed_key => key_bindings.input_cmd(key),
+            _ => None,
+        };
+
+        match (edit_column, input_cmd, key) {
+            (_, Some(InputCmd::Move(Movement::Up)), _) => {
+                moved_cursor = Some(move_up(menu_cursor));
+            }
+            (_, Some(InputCmd::Move(Movement::Down)), _) => {
+                moved_cursor = Some(move_down(menu_cursor));
+            }
+            // Pointing at a row moves the cursor to it. Clicking a row starts editing whichever
+            // column was clicked and clicking the back button goes back.
+            (_, Some(InputCmd::Mouse(event)), _) => {
+                if let Some((list_rect, char_count_col)) = list_layout {
+                    let pointed_at_row = solver_row_at(&list_rect, event.y, event.x);
+                    match (pointed_at_row, event.action) {
+                        (Some(row), MouseAction::Click) if row == rows.len() => break,
+                        (Some(row), MouseAction::Click) => {
+                            edit_column = if event.x >= char_count_col {
+                                EditColumn::CharCount
+                            } else {
+                                EditColumn::Word
+                            };
+                            moved_cursor = Some(row);
+                        }
+                        (Some(row), MouseAction::Hover) if row != menu_cursor => {
+                            moved_cursor = Some(row);
+                        }
+                        _ => (),
+                    }
+                }
+            }
+            (_, _, Some(Key::Tab)) => {
+                edit_column = match edit_column {
+                    EditColumn::Word => EditColumn::CharCount,
+                    EditColumn::CharCount => EditColumn::Word,
+                };
+                moved_cursor = Some(menu_cursor);
+            }
+            (_, _, Some(Key::Delete)) if menu_cursor < rows.len() => {
+                rows.remove(menu_cursor);
+                refresh_filtered_passwords = true;
+            }
+            (EditColumn::Word, _, Some(Key::Char(ch))) if ch.is_ascii_alphabetic() => {
+                // typing past the end of the list starts a new word
+                if menu_cursor == rows.len() {
+                    rows.push(SolverRow::new(String::new()));
+                }
+                rows[menu_cursor].word.push(ch.to_ascii_lowercase());
+                refresh_filtered_passwords = true;
+            }
+            (EditColumn::Word, _, Some(Key::Backspace)) if menu_cursor < rows.len() => {
+                if rows[menu_cursor].word.pop().is_none() {
+                    // backspacing an empty word removes it
+                    rows.remove(menu_cursor);
+                    menu_cursor = move_up(menu_cursor);
+                }
+                refresh_filtered_passwords = true;
+            }
+            // Selecting or typing any separator finishes the word and starts the next one. This
+            // also lets a whole list of words be pasted in at once.
+            (EditColumn::Word, Some(InputCmd::Select), _)
+            | (EditColumn::Word, _, Some(Key::Char(' ')))
+            | (EditColumn::Word, _, Some(Key::Char(','))) => {
+                if menu_cursor == rows.len() {
+                    break;
+                } else if !rows[menu_cursor].word.is_empty() {
+                    rows.insert(menu_cursor + 1, SolverRow::new(String::new()));
+                    moved_cursor = Some(menu_cursor + 1);
+                }
+            }
+            (EditColumn::Word, Some(InputCmd::Quit), _) => {
+                edit_column = EditColumn::CharCount;
+                moved_cursor = Some(menu_cursor);
+            }
+            (EditColumn::CharCount, Some(InputCmd::Select), _) => {
+                if menu_cursor == rows.len() {
+                    break;
+                } else {
+                    refresh_filtered_passwords = true;
+                    clear_on_next_number_input = true;
+                }
+            }
+            (EditColumn::CharCount, _, Some(Key::Char(ch)))
+                if ch.is_ascii_digit() && menu_cursor < rows.len() =>
+            {
+                let number_input_buffer = &mut rows[menu_cursor].char_count_input;
+                if clear_on_next_number_input {
+                    clear_on_next_number_input = false;
+                    *number_input_buffer = String::new();
+                }
+
+                if number_input_buffer.len() < 2 {
+                    number_input_buffer.push(ch);
+                }
+            }
+            (EditColumn::CharCount, _, Some(Key::Backspace)) if menu_cursor < rows.len() => {
+                rows[menu_cursor].char_count_input.pop();
+            }
+            (EditColumn::CharCount, Some(InputCmd::Quit), _) => break,
+            _ => (),
+        };
+
+        if let Some(next_cursor) = moved_cursor {
+            menu_cursor = prune_empty_rows(&mut rows, next_cursor);
+            refresh_filtered_passwords = true;
+            clear_on_next_number_input = true;
+        }
+        menu_cursor = std::cmp::min(menu_cursor, rows.len());
+
+        // The list can change size as words are typed so lay it out fresh every frame
+        let word_column_width = rows
+            .iter()
+            .map(|row| row.word.len() as i32 + 1) // leave room for the typing cursor
+            .max()
+            .unwrap_or(0)
+            .max(MIN_WORD_COLUMN_WIDTH);
+        let menu_rect = {
+            let menu_width = cursor_prefix_len
+                + word_column_width
+                + padding_width
+                + char_count_column_width
+                + issue_marker_column_width;
+            let menu_height = rows.len() as i32;
+
+            Rect {
+                // center the menu options horizontally
+                left: (window.rect().width - menu_width) / 2,
+                // center the menu options vertically
+                top: (window.rect().height - menu_height) / 2,
+                width: menu_width,
+                height: menu_height,
+            }
+        };
+        // the list is centered with the back button below it and the help text along the bottom
+        let min_width = menu_rect.width.max(HELP_TEXT.len() as i32);
+        let min_height = menu_rect.height + 5;
+        if !window.rect().fits(min_width, min_height) {
+            list_layout = None;
+            render_too_small_message(window, min_width, min_height);
+            std::thread::sleep(std::time::Duration::from_millis(33));
+            continue;
+        }
+
+        let col_offset = menu_rect.left + cursor_prefix_len;
+        let char_count_col = col_offset + word_column_width + padding_width;
+        list_layout = Some((menu_rect, char_count_col));
+
+        window.erase();
+
+        for (i, row) in rows.iter().enumerate() {
+            let pwd = &row.word;
+            let row_y = i as i32 + menu_rect.top;
+            let is_filtered_out = row.issue.is_some() || !filtered_input_passwords.contains(pwd);
+            if is_filtered_out {
+                window.attron(Attrs::DIM);
+            }
+            let is_recommended_guess = match &recommended_guess {
+                Some((recommended_pwd, _)) => recommended_pwd == pwd,
+                None => false,
+            };
+            if is_recommended_guess {
+                window.attron(Attrs::GREEN | Attrs::BOLD);
+            }
+            window.mvaddstr(row_y, col_offset, pwd);
+            window.attroff(Attrs::GREEN | Attrs::BOLD);
+
+            let (is_conflicting_guess, is_likely_typo) = match &guess_contradiction {
+                Some((contradiction, _)) => (
+                    contradiction.conflicting_guesses.contains(&i),
+                    matches!(contradiction.likely_typo, Some((typo_row, _)) if typo_row == i),
+                ),
+                None => (false, false),
+            };
+            let char_count_attr = if is_conflicting_guess {
+                Attrs::REVERSE
+            } else {
+                Attrs::UNDERLINE
+            };
+            window.attron(char_count_attr);
+            window.mvaddstr(
+                row_y,
+                char_count_col,
+                &format!("{:>2}", row.char_count_input),
+            );
+            window.attroff(char_count_attr);
+            window.attroff(Attrs::DIM);
+
+            let marker_col = char_count_col + char_count_column_width + 1;
+            if row.issue.is_some() {
+                window.attron(Attrs::BOLD);
+                window.mvaddstr(row_y, marker_col, "!");
+                window.attroff(Attrs::BOLD);
+            } else if is_likely_typo {
+                window.attron(Attrs::BOLD);
+                window.mvaddstr(row_y, marker_col, "?");
+                window.attroff(Attrs::BOLD);
+            }
+        }
+
+        let back_button_row = menu_rect.bottom() + 1;
+        let back_button_text = "[ Back ]";
+        window.mvaddstr(back_button_row, col_offset, back_button_text);
+
+        // Show what's wrong with the word under the cursor, otherwise how the solve is going
+        let mut status_row = back_button_row + 2;
+        let cursor_word_issue = rows.get(menu_cursor).and_then(|row| row.issue.as_ref());
+        if let Some(issue) = cursor_word_issue {
+            window.attron(Attrs::BOLD);
+            window.mvaddstr(status_row, col_offset, &issue.describe());
+            window.attroff(Attrs::BOLD);
+            status_row += 2;
+        }
+
+        if let Some((recommended_pwd, score)) = &recommended_guess {
+            let recommendation_text = if filtered_input_passwords.len() == 1 {
+                format!("Password: {}", recommended_pwd)
+            } else {
+                format!(
+                    "Best guess: {} ({} left at worst, {:.1} expected)",
+                    recommended_pwd,
+                    score.worst_case_remaining,
+                    score.expected_remaining(filtered_input_passwords.len()),
+                )
+            };
+            window.attron(Attrs::GREEN);
+            window.mvaddstr(status_row, col_offset, &recommendation_text);
+            window.attroff(Attrs::GREEN);
+        }
+
+        if let Some((_, description)) = &guess_contradiction {
+            const CONTRADICTION_TEXT_WIDTH: usize = 40;
+            window.attron(Attrs::BOLD);
+            for (i, line) in wrap_text(description, CONTRADICTION_TEXT_WIDTH)
+                .iter()
+                .enumerate()
+            {
+                window.mvaddstr(status_row + i as i32, col_offset, line);
+            }
+            window.attroff(Attrs::BOLD);
+        }
+
+        window.attron(Attrs::DIM);
+        window.mvaddstr(
+            window.rect().bottom() - 1,
+            (window.rect().width - HELP_TEXT.len() as i32) / 2,
+            HELP_TEXT,
+        );
+        window.attroff(Attrs::DIM);
+
+        if menu_cursor < rows.len() {
+            let cursor_row = menu_rect.top + menu_cursor as i32;
+            window.mvaddstr(cursor_row, menu_rect.left, cursor_prefix);
+            match edit_column {
+                EditColumn::CharCount => {
+                    window.mvchgat(
+                        cursor_row,
+                        char_count_col,
+                        char_count_column_width,
+                        Attrs::BLINK,
+                    );
+                }
+                EditColumn::Word => {
+                    let typing_cursor_col = col_offset + rows[menu_cursor].word.len() as i32;
+                    window.mvaddch(cursor_row, typing_cursor_col, '_');
+                    window.mvchgat(
+                        cursor_row,
+                        col_offset,
+                        rows[menu_cursor].word.len() as i32 + 1,
+                        Attrs::BLINK,
+                    );
+                }
+            }
+        } else {
+            assert_eq!(menu_cursor, rows.len());
+            window.mvchgat(
+                back_button_row,
+                col_offset,
+                back_button_text.len() as i32,
+                Attrs::BLINK,
+            );
+        }
+
+        window.refresh();
+
+        // No need to waste cycles doing nothing but rendering over and over.
+        // Yield the processor until the next frame.
+        std::thread::sleep(std::time::Duration::from_millis(33));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::headless::HeadlessTerminal;
+
+    #[test]
+    fn check_find_word_issues() {
+        let dict = EnglishDictChunk::from_word_list(5, "apple\ngrape\nseeds");
+        let mut is_word = |word: &str| Some(dict.is_word(word));
+
+        // the most common length wins, so the one short word is the one flagged
+        let words = ["apple", "grap", "seedz", "", "grape", "apple"];
+        assert_eq!(
+            find_word_issues(&words, &mut is_word),
+            vec![
+                None,
+                Some(WordIssue::WrongLength(5)),
+                Some(WordIssue::NotInEnglishDict),
+                None,
+                None,
+                Some(WordIssue::Duplicate),
+            ]
+        );
+
+        let mut no_dict = |_: &str| None;
+        assert_eq!(
+            find_word_issues(&["apple"], &mut no_dict),
+            vec![Some(WordIssue::EnglishDictUnavailable)]
+        );
+    }
+
+    #[test]
+    fn check_solver_snapshot() {
+        let passwords = ["dude", "rude", "cube", "dire"]
+            .iter()
+            .map(|w| String::from(*w))
+            .collect();
+        let guesses = vec![KnownGuess::new("dude", 3)];
+
+        // the frame is drawn after each key so the first one leaves something to look at
+        let terminal = HeadlessTerminal::new(60, 14);
+        terminal.push_keys(&[Key::Char('x'), Key::Esc]);
+        solver(
+            passwords,
+            guesses,
+            &DictSource::Builtin,
+            &KeyBindings::default(),
+            &terminal,
+        );
+
+        let expected_text = r#"
+
+
+
+
+                   > dude             3
+                     rude
+                     cube
+                     dire
+
+                     [ Back ]
+
+                     Password: rude
+    TAB: edit words/counts  DEL: remove word  ESC: back
+"#;
+        assert_eq!(terminal.text(), expected_text);
+        // only rude is left so it's recommended and the rest are dimmed
+        assert_eq!(terminal.cell(6, 21).attrs, Attrs::GREEN | Attrs::BOLD);
+        assert_eq!(terminal.cell(5, 21).attrs, Attrs::DIM);
+        assert_eq!(terminal.cell(7, 37).attrs, Attrs::DIM | Attrs::UNDERLINE);
+        // the cursor blinks over the char count being edited
+        assert_eq!(terminal.cell(5, 37).attrs, Attrs::BLINK);
+    }
+
+    #[test]
+    fn check_solver_too_small() {
+        let passwords = ["dude", "rude", "cube", "dire"]
+            .iter()
+            .map(|w| String::from(*w))
+            .collect();
+
+        let terminal = HeadlessTerminal::new(40, 8);
+        terminal.push_keys(&[Key::Char('x'), Key::Esc]);
+        solver(
+            passwords,
+            Vec::new(),
+            &DictSource::Builtin,
+            &KeyBindings::default(),
+            &terminal,
+        );
+
+        let expected_text = r#"
+
+           Terminal too small
+               Need 51x9
+               Have 40x8
+
+
+
+"#;
+        assert_eq!(terminal.text(), expected_text);
+    }
+
+    #[test]
+    fn check_solver_row_at() {
+        let list_rect = Rect {
+            left: 10,
+            top: 4,
+            width: 20,
+            height: 3,
+        };
+        assert_eq!(solver_row_at(&list_rect, 4, 10), Some(0));
+        assert_eq!(solver_row_at(&list_rect, 6, 29), Some(2));
+        // the back button comes after a blank row
+        assert_eq!(solver_row_at(&list_rect, 7, 12), None);
+        assert_eq!(solver_row_at(&list_rect, 8, 12), Some(3));
+        assert_eq!(solver_row_at(&list_rect, 5, 30), None);
+        assert_eq!(solver_row_at(&list_rect, 3, 12), None);
+    }
+
+    #[test]
+    fn check_prune_empty_rows() {
+        let mut rows: Vec<SolverRow> = ["apple", "", "grape", ""]
+            .iter()
+            .map(|w| SolverRow::new(String::from(*w)))
+            .collect();
+
+        // the empty row under the cursor is kept since it's still being typed in
+        let cursor = prune_empty_rows(&mut rows, 3);
+        assert_eq!(cursor, 2);
+        let words: Vec<&str> = rows.iter().map(|row| row.word.as_str()).collect();
+        assert_eq!(words, ["apple", "grape", ""]);
+    }
+}
@@ -0,0 +1,542 @@
+use crate::app_dirs;
+use crate::game::GameResult;
+use crate::tui::{render_too_small_message, show_error_panel, Attrs, Terminal};
+use fonv_cracker::board::{Difficulty, ALL_DIFFICULTIES};
+use fonv_cracker::game_state::GameOutcome;
+use std::path::PathBuf;
+use std::time::Duration;
+
+// Every stats file starts with this followed by the version it was written in. Files from older
+// versions keep loading and get rewritten in the current version the next time a game is saved.
+const STATS_HEADER_PREFIX: &str = "fonv_cracker stats v";
+const STATS_VERSION: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRecord {
+    pub timestamp: u64, // seconds since the unix epoch
+    pub difficulty: Difficulty,
+    pub outcome: GameOutcome,
+    pub time_limit: Option<Duration>,
+    pub duration: Duration,
+    pub attempts_used: usize,
+    pub guesses: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum StatsErr {
+    NoDataDir,
+    ReadFile(PathBuf, std::io::Error),
+    WriteFile(PathBuf, std::io::Error),
+    MissingHeader,
+    UnsupportedVersion(u32),
+    InvalidLine(usize, String),
+}
+
+impl std::fmt::Display for StatsErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            StatsErr::NoDataDir => write!(f, "couldn't find a data directory to keep stats in"),
+            StatsErr::ReadFile(path, err) => {
+                write!(f, "couldn't read stats file {}: {}", path.display(), err)
+            }
+            StatsErr::WriteFile(path, err) => {
+                write!(f, "couldn't write stats file {}: {}", path.display(), err)
+            }
+            StatsErr::MissingHeader => write!(f, "the stats file is missing its header"),
+            StatsErr::UnsupportedVersion(version) => write!(
+                f,
+                "the stats file is version {} but only up to version {} is supported",
+                version, STATS_VERSION
+            ),
+            StatsErr::InvalidLine(line_num, line) => {
+                write!(f, "invalid stats file line {}: '{}'", line_num, line)
+            }
+        }
+    }
+}
+
+fn outcome_name(outcome: GameOutcome) -> &'static str {
+    match outcome {
+        GameOutcome::Won => "won",
+        GameOutcome::Lost => "lost",
+        GameOutcome::TimedOut => "timedout",
+        GameOutcome::Quit => "quit",
+    }
+}
+
+fn parse_outcome(name: &str) -> Option<GameOutcome> {
+    match name {
+        "won" => Some(GameOutcome::Won),
+        "lost" => Some(GameOutcome::Lost),
+        "timedout" => Some(GameOutcome::TimedOut),
+        "quit" => Some(GameOutcome::Quit),
+        _ => None,
+    }
+}
+
+fn stats_file_path() -> Result<PathBuf, StatsErr> {
+    let data_dir = app_dirs::data_dir().ok_or(StatsErr::NoDataDir)?;
+    Ok(data_dir.join("stats.txt"))
+}
+
+fn parse_guesses(guesses: &str) -> Vec<String> {
+    match guesses {
+        "-" => Vec::new(),
+        _ => guesses.split(',').map(String::from).collect(),
+    }
+}
+
+fn parse_time_limit(time_limit_ms: &str) -> Option<Option<Duration>> {
+    match time_limit_ms {
+        "-" => Some(None),
+        _ => Some(Some(Duration::from_millis(time_limit_ms.parse().ok()?))),
+    }
+}
+
+// Files from before v3 didn't keep the attempts used, so count every wrong guess as one. That's
+// only off for games where a bracket sequence reset the attempts.
+fn default_attempts_used(outcome: GameOutcome, guesses: &[String]) -> usize {
+    match outcome {
+        GameOutcome::Won => guesses.len().saturating_sub(1),
+        _ => guesses.len(),
+    }
+}
+
+// v1 lines look like "game 1700000000 Average won 41250 abates,banish", i.e. the time the game was
+// played, its difficulty, how it ended, how long it took in milliseconds and the words guessed
+fn parse_v1_line(line: &str) -> Option<GameRecord> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    match fields.as_slice() {
+        ["game", timestamp, difficulty, outcome, duration_ms, guesses] => {
+            let outcome = parse_outcome(outcome)?;
+            let guesses = parse_guesses(guesses);
+            Some(GameRecord {
+                timestamp: timestamp.parse().ok()?,
+                difficulty: difficulty.parse().ok()?,
+                outcome,
+                // timed games came after v1
+                time_limit: None,
+                duration: Duration::from_millis(duration_ms.parse().ok()?),
+                attempts_used: default_attempts_used(outcome, &guesses),
+                guesses,
+            })
+        }
+        _ => None,
+    }
+}
+
+// v2 adds the time limit in milliseconds before the duration, or "-" for untimed games, e.g.
+// "game 1700000000 Average won 120000 41250 abates,banish"
+fn parse_v2_line(line: &str) -> Option<GameRecord> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    match fields.as_slice() {
+        ["game", timestamp, difficulty, outcome, time_limit_ms, duration_ms, guesses] => {
+            let outcome = parse_outcome(outcome)?;
+            let guesses = parse_guesses(guesses);
+            Some(GameRecord {
+                timestamp: timestamp.parse().ok()?,
+                difficulty: difficulty.parse().ok()?,
+                outcome,
+                time_limit: parse_time_limit(time_limit_ms)?,
+                duration: Duration::from_millis(duration_ms.parse().ok()?),
+                attempts_used: default_attempts_used(outcome, &guesses),
+                guesses,
+            })
+        }
+        _ => None,
+    }
+}
+
+// v3 adds the attempts used after the duration, e.g.
+// "game 1700000000 Average won 120000 41250 1 abates,banish"
+fn parse_v3_line(line: &str) -> Option<GameRecord> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    match fields.as_slice() {
+        ["game", timestamp, difficulty, outcome, time_limit_ms, duration_ms, attempts_used, guesses] => {
+            Some(GameRecord {
+                timestamp: timestamp.parse().ok()?,
+                difficulty: difficulty.parse().ok()?,
+                outcome: parse_outcome(outcome)?,
+                time_limit: parse_time_limit(time_limit_ms)?,
+                duration: Duration::from_millis(duration_ms.parse().ok()?),
+                attempts_used: attempts_used.parse().ok()?,
+                guesses: parse_guesses(guesses),
+            })
+        }
+        _ => None,
+    }
+}
+
+fn parse_stats(text: &str) -> Result<Vec<GameRecord>, StatsErr> {
+    let mut lines = text.lines().map(|line| line.trim());
+    let version = lines
+        .next()
+        .and_then(|header| header.strip_prefix(STATS_HEADER_PREFIX))
+        .and_then(|version| version.parse::<u32>().ok())
+        .ok_or(StatsErr::MissingHeader)?;
+
+    let parse_line = match version {
+        1 => parse_v1_line,
+        2 => parse_v2_line,
+        3 => parse_v3_line,
+        _ => return Err(StatsErr::UnsupportedVersion(version)),
+    };
+
+    let mut records = Vec::new();
+    for (i, line) in lines.enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+
+        // the header was line 1
+        let line_num = i + 2;
+        let record =
+            parse_line(line).ok_or_else(|| StatsErr::InvalidLine(line_num, line.into()))?;
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+fn format_stats(records: &[GameRecord]) -> String {
+    let mut text = format!("{}{}\n", STATS_HEADER_PREFIX, STATS_VERSION);
+    for record in records {
+        let guesses = if record.guesses.is_empty() {
+            String::from("-")
+        } else {
+            record.guesses.join(",")
+        };
+        let time_limit = match record.time_limit {
+            Some(time_limit) => time_limit.as_millis().to_string(),
+            None => String::from("-"),
+        };
+        text.push_str(&format!(
+            "game {} {} {} {} {} {} {}\n",
+            record.timestamp,
+            record.difficulty,
+            outcome_name(record.outcome),
+            time_limit,
+            record.duration.as_millis(),
+            record.attempts_used,
+            guesses
+        ));
+    }
+    text
+}
+
+pub fn load_stats() -> Result<Vec<GameRecord>, StatsErr> {
+    let path = stats_file_path()?;
+    match std::fs::read_to_string(&path) {
+        Ok(text) => parse_stats(&text),
+        // nothing has been played yet
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(StatsErr::ReadFile(path, e)),
+    }
+}
+
+fn save_stats(records: &[GameRecord]) -> Result<(), StatsErr> {
+    let path = stats_file_path()?;
+    let write_err = |e| StatsErr::WriteFile(path.clone(), e);
+
+    if let Some(parent_dir) = path.parent() {
+        std::fs::create_dir_all(parent_dir).map_err(write_err)?;
+    }
+
+    // write the new stats alongside the old ones first so a failed write can't lose them
+    let temp_path = path.with_extension("txt.tmp");
+    std::fs::write(&temp_path, format_stats(records)).map_err(write_err)?;
+    std::fs::rename(&temp_path, &path).map_err(write_err)
+}
+
+// Add a finished game to the stats. Games quit before making a single guess aren't worth keeping.
+pub fn record_game(result: &GameResult) -> Result<(), StatsErr> {
+    if result.outcome == GameOutcome::Quit && result.guesses.is_empty() {
+        return Ok(());
+    }
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_secs())
+        .unwrap_or(0);
+
+    let mut records = load_stats()?;
+    records.push(GameRecord {
+        timestamp,
+        difficulty: result.difficulty,
+        outcome: result.outcome,
+        time_limit: result.time_limit,
+        duration: result.duration,
+        attempts_used: result.attempts_used,
+        guesses: result.guesses.clone(),
+    });
+    save_stats(&records)
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct DifficultyStats {
+    played: usize,
+    won: usize,
+    guesses_in_wins: usize,
+    attempts_used_in_wins: usize,
+    best_streak: usize,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct StatsSummary {
+    per_difficulty: Vec<(Difficulty, DifficultyStats)>,
+    current_streak: usize,
+    best_streak: usize,
+}
+
+// Streaks count consecutive wins in the order the games were played
+fn summarize(records: &[GameRecord]) -> StatsSummary {
+    let mut per_difficulty: Vec<(Difficulty, DifficultyStats)> = ALL_DIFFICULTIES
+        .iter()
+        .map(|d| (*d, DifficultyStats::default()))
+        .collect();
+    let mut difficulty_streaks = vec![0; ALL_DIFFICULTIES.len()];
+    let mut current_streak = 0;
+    let mut best_streak = 0;
+
+    for record in records {
+        let difficulty_index = ALL_DIFFICULTIES
+            .iter()
+            .position(|d| *d == record.difficulty)
+            .unwrap();
+        let stats = &mut per_difficulty[difficulty_index].1;
+        let difficulty_streak = &mut difficulty_streaks[difficulty_index];
+
+        stats.played += 1;
+        if record.outcome == GameOutcome::Won {
+            stats.won += 1;
+            stats.guesses_in_wins += record.guesses.len();
+            stats.attempts_used_in_wins += record.attempts_used;
+            *difficulty_streak += 1;
+            current_streak += 1;
+        } else {
+            *difficulty_streak = 0;
+            current_streak = 0;
+        }
+        stats.best_streak = stats.best_streak.max(*difficulty_streak);
+        best_streak = best_streak.max(current_streak);
+    }
+
+    StatsSummary {
+        per_difficulty,
+        current_streak,
+        best_streak,
+    }
+}
+
+fn format_summary_table(summary: &StatsSummary) -> Vec<String> {
+    let mut lines = vec![format!(
+        "{:<10} {:>6} {:>5} {:>6} {:>11} {:>12} {:>11}",
+        "DIFFICULTY", "PLAYED", "WON", "WIN %", "AVG GUESSES", "AVG ATTEMPTS", "BEST STREAK"
+    )];
+
+    for (difficulty, stats) in &summary.per_difficulty {
+        let win_rate = match stats.played {
+            0 => String::from("-"),
+            played => format!("{:.0}", 100.0 * stats.won as f64 / played as f64),
+        };
+        // both averages are over wins since every loss uses up all the attempts
+        let average_in_wins = |total: usize| match stats.won {
+            0 => String::from("-"),
+            won => format!("{:.1}", total as f64 / won as f64),
+        };
+
+        lines.push(format!(
+            "{:<10} {:>6} {:>5} {:>6} {:>11} {:>12} {:>11}",
+            difficulty.to_string(),
+            stats.played,
+            stats.won,
+            win_rate,
+            average_in_wins(stats.guesses_in_wins),
+            average_in_wins(stats.attempts_used_in_wins),
+            stats.best_streak
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push(format!(
+        "Current win streak: {}    Best win streak: {}",
+        summary.current_streak, summary.best_streak
+    ));
+    lines
+}
+
+pub fn run_stats_screen(window: &dyn Terminal) {
+    let records = match load_stats() {
+        Ok(records) => records,
+        Err(e) => {
+            show_error_panel(window, &format!("Couldn't load stats: {}", e));
+            return;
+        }
+    };
+
+    let table_lines = format_summary_table(&summarize(&records));
+    let table_width = table_lines.iter().map(|line| line.len()).max().unwrap() as i32;
+    const TITLE: &str = "STATISTICS";
+    const DISMISS_TEXT: &str = "Press any key to return";
+    // the title and the dismiss text each have a blank row between them and the table
+    let min_height = table_lines.len() as i32 + 4;
+
+    // clear out any keys pressed on the way in so they don't immediately dismiss the screen
+    window.flush_keys();
+    loop {
+        let window_rect = window.rect();
+        if window_rect.fits(table_width, min_height) {
+            let left = (window_rect.width - table_width) / 2;
+            let top = (window_rect.height - min_height) / 2;
+
+            window.erase();
+            window.attron(Attrs::GREEN);
+            window.mvaddstr(top, left, TITLE);
+            window.attroff(Attrs::GREEN);
+            for (i, line) in table_lines.iter().enumerate() {
+                window.mvaddstr(top + 2 + i as i32, left, line);
+            }
+            window.mvaddstr(top + 3 + table_lines.len() as i32, left, DISMISS_TEXT);
+            window.refresh();
+        } else {
+            render_too_small_message(window, table_width, min_height);
+        }
+
+        // resizing just lays the screen out again
+        if matches!(window.poll_key(), Some(key) if key.is_press()) {
+            break;
+        }
+
+        std::thread::sleep(std::time::Duration::from_millis(33));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(difficulty: Difficulty, outcome: GameOutcome, guesses: &[&str]) -> GameRecord {
+        let guesses: Vec<String> = guesses.iter().map(|g| String::from(*g)).collect();
+        GameRecord {
+            timestamp: 1_700_000_000,
+            difficulty,
+            outcome,
+            time_limit: None,
+            duration: Duration::from_millis(30_500),
+            attempts_used: default_attempts_used(outcome, &guesses),
+            guesses,
+        }
+    }
+
+    #[test]
+    fn check_stats_round_trip() {
+        let mut timed_record = record(Difficulty::Easy, GameOutcome::TimedOut, &["abates"]);
+        timed_record.time_limit = Some(Duration::from_secs(90));
+        let records = vec![
+            record(Difficulty::Average, GameOutcome::Won, &["abates", "banish"]),
+            record(Difficulty::Hard, GameOutcome::Quit, &[]),
+            timed_record,
+        ];
+
+        let text = format_stats(&records);
+        assert_eq!(
+            text,
+            "fonv_cracker stats v3\n\
+             game 1700000000 Average won - 30500 1 abates,banish\n\
+             game 1700000000 Hard quit - 30500 0 -\n\
+             game 1700000000 Easy timedout 90000 30500 1 abates\n"
+        );
+        assert_eq!(parse_stats(&text).unwrap(), records);
+
+        // attempts reset by a bracket sequence are kept as they were
+        let mut reset_record = record(Difficulty::Average, GameOutcome::Won, &["abates", "banish"]);
+        reset_record.attempts_used = 0;
+        let reset_records = vec![reset_record];
+        assert_eq!(
+            parse_stats(&format_stats(&reset_records)).unwrap(),
+            reset_records
+        );
+
+        // stats written before the attempts used were kept count each wrong guess as one
+        let v2_text = "fonv_cracker stats v2\n\
+                       game 1700000000 Average won - 30500 abates,banish\n\
+                       game 1700000000 Hard quit - 30500 -\n\
+                       game 1700000000 Easy timedout 90000 30500 abates\n";
+        assert_eq!(parse_stats(v2_text).unwrap(), records);
+
+        // stats written before timed games existed still load
+        let v1_text = "fonv_cracker stats v1\n\
+                       game 1700000000 Average won 30500 abates,banish\n\
+                       game 1700000000 Hard quit 30500 -\n";
+        assert_eq!(parse_stats(v1_text).unwrap(), records[..2]);
+    }
+
+    #[test]
+    fn check_invalid_stats() {
+        assert!(matches!(
+            parse_stats("game 1700000000 Hard quit 30500 -\n"),
+            Err(StatsErr::MissingHeader)
+        ));
+        assert!(matches!(
+            parse_stats("fonv_cracker stats v99\n"),
+            Err(StatsErr::UnsupportedVersion(99))
+        ));
+        assert!(matches!(
+            parse_stats("fonv_cracker stats v1\n\ngame 1700000000 Hard tied 30500 -\n"),
+            Err(StatsErr::InvalidLine(3, _))
+        ));
+        assert!(matches!(
+            parse_stats("fonv_cracker stats v2\ngame 1700000000 Hard quit 30500 -\n"),
+            Err(StatsErr::InvalidLine(2, _))
+        ));
+        assert!(matches!(
+            parse_stats("fonv_cracker stats v3\ngame 1700000000 Hard quit - 30500 -\n"),
+            Err(StatsErr::InvalidLine(2, _))
+        ));
+    }
+
+    #[test]
+    fn check_summarize() {
+        let records = vec![
+            record(Difficulty::Easy, GameOutcome::Won, &["a", "b", "c"]),
+            record(Difficulty::Easy, GameOutcome::Won, &["a"]),
+            record(Difficulty::Hard, GameOutcome::Lost, &["a", "b", "c", "d"]),
+            record(Difficulty::Easy, GameOutcome::Won, &["a", "b"]),
+            record(Difficulty::Hard, GameOutcome::Won, &["a", "b", "c", "d"]),
+        ];
+
+        let summary = summarize(&records);
+        assert_eq!(summary.current_streak, 2);
+        assert_eq!(summary.best_streak, 2);
+
+        let stats_for = |difficulty| {
+            &summary
+                .per_difficulty
+                .iter()
+                .find(|(d, _)| *d == difficulty)
+                .unwrap()
+                .1
+        };
+        assert_eq!(
+            *stats_for(Difficulty::Easy),
+            DifficultyStats {
+                played: 3,
+                won: 3,
+                guesses_in_wins: 6,
+                attempts_used_in_wins: 3,
+                best_streak: 3,
+            }
+        );
+        assert_eq!(
+            *stats_for(Difficulty::Hard),
+            DifficultyStats {
+                played: 2,
+                won: 1,
+                guesses_in_wins: 4,
+                attempts_used_in_wins: 3,
+                best_streak: 1,
+            }
+        );
+        assert_eq!(*stats_for(Difficulty::VeryHard), DifficultyStats::default());
+    }
+}
@@ -0,0 +1,683 @@
+use fonv_cracker::utils::str_utils::wrap_text;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub left: i32,
+    pub top: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+// Layout helpers. Screens carve the window up into panes by splitting off strips from its edges.
+// Splits clamp to the size of the rect so a pane can shrink to nothing but never goes negative.
+impl Rect {
+    pub const fn right(&self) -> i32 {
+        self.left + self.width
+    }
+
+    pub const fn bottom(&self) -> i32 {
+        self.top + self.height
+    }
+
+    pub const fn fits(&self, width: i32, height: i32) -> bool {
+        width <= self.width && height <= self.height
+    }
+
+    pub const fn contains(&self, y: i32, x: i32) -> bool {
+        y >= self.top && y < self.bottom() && x >= self.left && x < self.right()
+    }
+
+    // (the top `height` rows, the rest)
+    pub fn split_top(&self, height: i32) -> (Rect, Rect) {
+        let height = height.max(0).min(self.height);
+        let top = Rect { height, ..*self };
+        let rest = Rect {
+            top: self.top + height,
+            height: self.height - height,
+            ..*self
+        };
+        (top, rest)
+    }
+
+    // (the rest, the bottom `height` rows)
+    pub fn split_bottom(&self, height: i32) -> (Rect, Rect) {
+        let (rest, bottom) = self.split_top(self.height - height.max(0));
+        (rest, bottom)
+    }
+
+    // (the left `width` columns, the rest)
+    pub fn split_left(&self, width: i32) -> (Rect, Rect) {
+        let width = width.max(0).min(self.width);
+        let left = Rect { width, ..*self };
+        let rest = Rect {
+            left: self.left + width,
+            width: self.width - width,
+            ..*self
+        };
+        (left, rest)
+    }
+
+    // (the rest, the right `width` columns)
+    pub fn split_right(&self, width: i32) -> (Rect, Rect) {
+        let (rest, right) = self.split_left(self.width - width.max(0));
+        (rest, right)
+    }
+
+    // A rect of the given size in the middle of this one
+    pub fn centered(&self, width: i32, height: i32) -> Rect {
+        Rect {
+            left: self.left + (self.width - width) / 2,
+            top: self.top + (self.height - height) / 2,
+            width,
+            height,
+        }
+    }
+}
+
+// Text attributes the screens draw with. Each backend maps them onto whatever its terminal supports.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Attrs(u8);
+
+impl Attrs {
+    pub const BOLD: Attrs = Attrs(1 << 0);
+    pub const DIM: Attrs = Attrs(1 << 1);
+    pub const BLINK: Attrs = Attrs(1 << 2);
+    pub const UNDERLINE: Attrs = Attrs(1 << 3);
+    pub const REVERSE: Attrs = Attrs(1 << 4);
+    pub const GREEN: Attrs = Attrs(1 << 5);
+
+    pub const fn contains(self, other: Attrs) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn without(self, other: Attrs) -> Attrs {
+        Attrs(self.0 & !other.0)
+    }
+}
+
+impl std::ops::BitOr for Attrs {
+    type Output = Attrs;
+
+    fn bitor(self, other: Attrs) -> Attrs {
+        Attrs(self.0 | other.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseAction {
+    Hover,
+    Click,
+}
+
+// Where the mouse is, in window coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MouseEvent {
+    pub action: MouseAction,
+    pub y: i32,
+    pub x: i32,
+}
+
+// The keys screens respond to. Anything else still counts as a key press, e.g. to dismiss a panel.
+// Backends report control keys as their own variants rather than as chars, however the terminal
+// happens to send them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    Char(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Esc,
+    Tab,
+    Backspace,
+    Delete,
+    // 5 on the numpad with num lock off
+    KeypadCenter,
+    // The window changed size. Screens pick up the new size from the terminal's rect.
+    Resize,
+    Mouse(MouseEvent),
+    Other,
+}
+
+impl Key {
+    // Whether this counts as pressing any key. The window resizing or the mouse moving doesn't.
+    pub fn is_press(&self) -> bool {
+        !matches!(
+            self,
+            Key::Resize
+                | Key::Mouse(MouseEvent {
+                    action: MouseAction::Hover,
+                    ..
+                })
+        )
+    }
+}
+
+// Everything the screens need from a terminal: somewhere to draw and a keyboard to poll. The
+// drawing calls follow curses, where text is drawn with whatever attributes are currently on and
+// nothing shows up until the next refresh.
+pub trait Terminal {
+    fn rect(&self) -> Rect;
+    fn erase(&self);
+    fn refresh(&self);
+    fn mvaddstr(&self, y: i32, x: i32, text: &str);
+    fn mvaddch(&self, y: i32, x: i32, ch: char);
+    fn attron(&self, attrs: Attrs);
+    fn attroff(&self, attrs: Attrs);
+    // Replace the attributes of `len` cells without touching their text
+    fn mvchgat(&self, y: i32, x: i32, len: i32, attrs: Attrs);
+    // The next key pressed or mouse event, without waiting for one
+    fn poll_key(&self) -> Option<Key>;
+    // Throw away any keys pressed but not polled yet
+    fn flush_keys(&self);
+}
+
+// Shown in place of a screen when the window can't fit it
+pub fn render_too_small_message(window: &dyn Terminal, min_width: i32, min_height: i32) {
+    let window_rect = window.rect();
+    let lines = [
+        String::from("Terminal too small"),
+        format!("Need {}x{}", min_width, min_height),
+        format!("Have {}x{}", window_rect.width, window_rect.height),
+    ];
+
+    window.erase();
+    for (i, line) in lines.iter().enumerate() {
+        let line_rect = window_rect.centered(line.len() as i32, lines.len() as i32);
+        window.mvaddstr(line_rect.top.max(0) + i as i32, line_rect.left.max(0), line);
+    }
+    window.refresh();
+}
+
+const ERROR_PANEL_WIDTH: i32 = 50;
+const ERROR_PANEL_TEXT_PADDING: i32 = 2;
+
+// The panel shrinks to fit narrow windows, wrapping the message to whatever width is left
+fn render_error_panel(window: &dyn Terminal, error_msg: &str) {
+    const DISMISS_TEXT: &str = "Press any key to continue";
+
+    let panel_width = ERROR_PANEL_WIDTH.min(window.rect().width);
+    let text_width = (panel_width - ERROR_PANEL_TEXT_PADDING * 2).max(1);
+    let msg_lines = wrap_text(error_msg, text_width as usize);
+    let dismiss_lines = wrap_text(DISMISS_TEXT, text_width as usize);
+    // border, title, blank, msg, blank, dismiss text, border
+    let panel_height = msg_lines.len() as i32 + dismiss_lines.len() as i32 + 5;
+    let panel_rect = window.rect().centered(panel_width, panel_height);
+
+    window.erase();
+
+    let horizontal_border = format!("+{}+", "-".repeat((panel_rect.width - 2).max(0) as usize));
+    window.mvaddstr(panel_rect.top, panel_rect.left, &horizontal_border);
+    for row in 1..(panel_rect.height - 1) {
+        window.mvaddch(panel_rect.top + row, panel_rect.left, '|');
+        window.mvaddch(
+            panel_rect.top + row,
+            panel_rect.left + panel_rect.width - 1,
+            '|',
+        );
+    }
+    window.mvaddstr(
+        panel_rect.top + panel_rect.height - 1,
+        panel_rect.left,
+        &horizontal_border,
+    );
+
+    let text_left = panel_rect.left + ERROR_PANEL_TEXT_PADDING;
+    window.attron(Attrs::BOLD);
+    window.mvaddstr(panel_rect.top + 1, text_left, "ERROR");
+    window.attroff(Attrs::BOLD);
+    for (i, line) in msg_lines.iter().enumerate() {
+        window.mvaddstr(panel_rect.top + 3 + i as i32, text_left, line);
+    }
+    window.attron(Attrs::DIM);
+    let dismiss_top = panel_rect.bottom() - 1 - dismiss_lines.len() as i32;
+    for (i, line) in dismiss_lines.iter().enumerate() {
+        window.mvaddstr(dismiss_top + i as i32, text_left, line);
+    }
+    window.attroff(Attrs::DIM);
+    window.refresh();
+}
+
+// Show an error message in a box in the middle of the window until any key is pressed
+pub fn show_error_panel(window: &dyn Terminal, error_msg: &str) {
+    render_error_panel(window, error_msg);
+
+    // throw away anything typed before the panel was shown so it isn't dismissed immediately
+    window.flush_keys();
+    loop {
+        match window.poll_key() {
+            Some(Key::Resize) => render_error_panel(window, error_msg),
+            Some(key) if key.is_press() => break,
+            Some(_) => (),
+            None => std::thread::sleep(std::time::Duration::from_millis(33)),
+        }
+    }
+}
+
+pub mod pancurses {
+    use super::{ascii_keycodes, Attrs, Key, MouseAction, MouseEvent, Rect, Terminal};
+    use std::io::Write;
+
+    // Asks xterm compatible terminals to report the mouse moving even when no button is held.
+    // Curses only asks for motion while a button is down, which isn't enough to hover.
+    const ENABLE_MOUSE_MOTION: &str = "\x1b[?1003h";
+    const DISABLE_MOUSE_MOTION: &str = "\x1b[?1003l";
+
+    const GREEN_IDX: u8 = 1;
+
+    pub fn setup_window(title: &str) -> pancurses::Window {
+        let window = pancurses::initscr();
+        pancurses::start_color();
+        pancurses::init_pair(
+            GREEN_IDX as i16,
+            pancurses::COLOR_GREEN,
+            pancurses::COLOR_BLACK,
+        );
+        pancurses::noecho(); // prevent key inputs rendering to the screen
+        pancurses::cbreak();
+        pancurses::curs_set(0);
+        pancurses::set_title(title);
+        window.nodelay(true); // don't block waiting for key inputs (we'll poll)
+        window.keypad(true); // let special keys be captured by the program (i.e. esc/backspace/del/arrow keys)
+
+        pancurses::mousemask(
+            pancurses::BUTTON1_PRESSED | pancurses::REPORT_MOUSE_POSITION,
+            std::ptr::null_mut(),
+        );
+        pancurses::mouseinterval(0); // report presses straight away instead of waiting for a click
+        if cfg!(unix) {
+            print!("{}", ENABLE_MOUSE_MOTION);
+            let _ = std::io::stdout().flush();
+        }
+        window
+    }
+
+    pub fn teardown_window() {
+        if cfg!(unix) {
+            print!("{}", DISABLE_MOUSE_MOTION);
+            let _ = std::io::stdout().flush();
+        }
+        pancurses::endwin();
+    }
+
+    fn to_chtype(attrs: Attrs) -> pancurses::chtype {
+        let mapping = [
+            (Attrs::BOLD, pancurses::A_BOLD),
+            (Attrs::DIM, pancurses::A_DIM),
+            (Attrs::BLINK, pancurses::A_BLINK),
+            (Attrs::UNDERLINE, pancurses::A_UNDERLINE),
+            (Attrs::REVERSE, pancurses::A_REVERSE),
+            (
+                Attrs::GREEN,
+                pancurses::COLOR_PAIR(GREEN_IDX as pancurses::chtype),
+            ),
+        ];
+        mapping
+            .iter()
+            .filter(|(attr, _)| attrs.contains(*attr))
+            .fold(pancurses::A_NORMAL, |chtype, (_, attr_chtype)| {
+                chtype | attr_chtype
+            })
+    }
+
+    impl Terminal for pancurses::Window {
+        fn rect(&self) -> Rect {
+            let (height, width) = self.get_max_yx();
+            Rect {
+                left: 0,
+                top: 0,
+                width,
+                height,
+            }
+        }
+
+        fn erase(&self) {
+            pancurses::Window::erase(self);
+        }
+
+        fn refresh(&self) {
+            pancurses::Window::refresh(self);
+        }
+
+        fn mvaddstr(&self, y: i32, x: i32, text: &str) {
+            pancurses::Window::mvaddstr(self, y, x, text);
+        }
+
+        fn mvaddch(&self, y: i32, x: i32, ch: char) {
+            pancurses::Window::mvaddch(self, y, x, ch);
+        }
+
+        fn attron(&self, attrs: Attrs) {
+            pancurses::Window::attron(self, to_chtype(attrs));
+        }
+
+        fn attroff(&self, attrs: Attrs) {
+            pancurses::Window::attroff(self, to_chtype(attrs));
+        }
+
+        fn mvchgat(&self, y: i32, x: i32, len: i32, attrs: Attrs) {
+            // the color pair is passed separately from the rest of the attributes
+            let color_pair = if attrs.contains(Attrs::GREEN) {
+                GREEN_IDX as i16
+            } else {
+                0
+            };
+            let attrs = to_chtype(attrs.without(Attrs::GREEN));
+            pancurses::Window::mvchgat(self, y, x, len, attrs, color_pair);
+        }
+
+        fn poll_key(&self) -> Option<Key> {
+            let key = match self.getch()? {
+                pancurses::Input::Character(ascii_keycodes::ENTER)
+                | pancurses::Input::Character('\r') => Key::Enter,
+                pancurses::Input::Character(ascii_keycodes::ESC) => Key::Esc,
+                pancurses::Input::Character('\t') => Key::Tab,
+                pancurses::Input::Character(ascii_keycodes::BKSP)
+                | pancurses::Input::Character(ascii_keycodes::DEL) => Key::Backspace,
+                pancurses::Input::Character(ch) => Key::Char(ch),
+                pancurses::Input::KeyUp => Key::Up,
+                pancurses::Input::KeyDown => Key::Down,
+                pancurses::Input::KeyLeft => Key::Left,
+                pancurses::Input::KeyRight => Key::Right,
+                pancurses::Input::KeyEnter => Key::Enter,
+                pancurses::Input::KeyBackspace => Key::Backspace,
+                pancurses::Input::KeyDC => Key::Delete,
+                pancurses::Input::KeyB2 => Key::KeypadCenter,
+                pancurses::Input::KeyMouse => match pancurses::getmouse() {
+                    Ok(event) => {
+                        let action = if event.bstate & pancurses::BUTTON1_PRESSED != 0 {
+                            MouseAction::Click
+                        } else {
+                            MouseAction::Hover
+                        };
+                        Key::Mouse(MouseEvent {
+                            action,
+                            y: event.y,
+                            x: event.x,
+                        })
+                    }
+                    Err(_) => Key::Other,
+                },
+                pancurses::Input::KeyResize => {
+                    // ncurses has already resized itself by the time it reports the key but
+                    // PDCurses needs to be told to pick up the new size
+                    if cfg!(windows) {
+                        pancurses::resize_term(0, 0);
+                    }
+                    Key::Resize
+                }
+                _ => Key::Other,
+            };
+            Some(key)
+        }
+
+        fn flush_keys(&self) {
+            pancurses::flushinp();
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod headless {
+    use super::{Attrs, Key, Rect, Terminal};
+    use std::cell::{Cell, RefCell};
+    use std::collections::VecDeque;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct ScreenCell {
+        pub ch: char,
+        pub attrs: Attrs,
+    }
+
+    const BLANK_CELL: ScreenCell = ScreenCell {
+        ch: ' ',
+        attrs: Attrs(0),
+    };
+
+    // A terminal which draws into a grid in memory and plays back queued up keys so screens can be
+    // run and checked without a real terminal. Drawing off the edge of the grid is clipped rather
+    // than wrapped onto the next row.
+    pub struct HeadlessTerminal {
+        width: i32,
+        height: i32,
+        cells: RefCell<Vec<ScreenCell>>,
+        attrs: Cell<Attrs>,
+        keys: RefCell<VecDeque<Key>>,
+    }
+
+    impl HeadlessTerminal {
+        pub fn new(width: i32, height: i32) -> Self {
+            HeadlessTerminal {
+                width,
+                height,
+                cells: RefCell::new(vec![BLANK_CELL; (width * height) as usize]),
+                attrs: Cell::new(Attrs::default()),
+                keys: RefCell::new(VecDeque::new()),
+            }
+        }
+
+        pub fn push_keys(&self, keys: &[Key]) {
+            self.keys.borrow_mut().extend(keys);
+        }
+
+        fn index(&self, y: i32, x: i32) -> Option<usize> {
+            let in_bounds = (0..self.height).contains(&y) && (0..self.width).contains(&x);
+            if in_bounds {
+                Some((y * self.width + x) as usize)
+            } else {
+                None
+            }
+        }
+
+        pub fn cell(&self, y: i32, x: i32) -> ScreenCell {
+            let index = self.index(y, x).expect("cell is off the screen");
+            self.cells.borrow()[index]
+        }
+
+        pub fn row_text(&self, y: i32) -> String {
+            let row: String = (0..self.width).map(|x| self.cell(y, x).ch).collect();
+            String::from(row.trim_end())
+        }
+
+        // The whole screen with one line per row and trailing spaces trimmed
+        pub fn text(&self) -> String {
+            (0..self.height).map(|y| self.row_text(y) + "\n").collect()
+        }
+    }
+
+    impl Terminal for HeadlessTerminal {
+        fn rect(&self) -> Rect {
+            Rect {
+                left: 0,
+                top: 0,
+                width: self.width,
+                height: self.height,
+            }
+        }
+
+        fn erase(&self) {
+            for cell in self.cells.borrow_mut().iter_mut() {
+                *cell = BLANK_CELL;
+            }
+        }
+
+        fn refresh(&self) {}
+
+        fn mvaddstr(&self, y: i32, x: i32, text: &str) {
+            for (i, ch) in text.chars().enumerate() {
+                self.mvaddch(y, x + i as i32, ch);
+            }
+        }
+
+        fn mvaddch(&self, y: i32, x: i32, ch: char) {
+            if let Some(index) = self.index(y, x) {
+                let attrs = self.attrs.get();
+                self.cells.borrow_mut()[index] = ScreenCell { ch, attrs };
+            }
+        }
+
+        fn attron(&self, attrs: Attrs) {
+            self.attrs.set(self.attrs.get() | attrs);
+        }
+
+        fn attroff(&self, attrs: Attrs) {
+            self.attrs.set(self.attrs.get().without(attrs));
+        }
+
+        fn mvchgat(&self, y: i32, x: i32, len: i32, attrs: Attrs) {
+            for col in x..x + len {
+                if let Some(index) = self.index(y, col) {
+                    self.cells.borrow_mut()[index].attrs = attrs;
+                }
+            }
+        }
+
+        fn poll_key(&self) -> Option<Key> {
+            self.keys.borrow_mut().pop_front()
+        }
+
+        // queued keys stand in for ones pressed after the screen is up so they're kept
+        fn flush_keys(&self) {}
+    }
+}
+
+pub mod ascii_keycodes {
+    pub const ESC: char = 27 as char;
+    pub const BKSP: char = 8 as char;
+    pub const DEL: char = 127 as char;
+    pub const ENTER: char = 10 as char;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rect_splits() {
+        let rect = Rect {
+            left: 2,
+            top: 1,
+            width: 10,
+            height: 6,
+        };
+
+        let (top, rest) = rect.split_top(2);
+        assert_eq!(top, Rect { height: 2, ..rect });
+        assert_eq!(
+            rest,
+            Rect {
+                top: 3,
+                height: 4,
+                ..rect
+            }
+        );
+
+        let (rest, right) = rect.split_right(3);
+        assert_eq!(rest, Rect { width: 7, ..rect });
+        assert_eq!(
+            right,
+            Rect {
+                left: 9,
+                width: 3,
+                ..rect
+            }
+        );
+        assert_eq!(right.right(), rect.right());
+
+        // splitting off more than there is leaves nothing behind rather than going negative
+        let (left, rest) = rect.split_left(20);
+        assert_eq!(left, rect);
+        assert_eq!(rest.width, 0);
+        let (rest, bottom) = rect.split_bottom(20);
+        assert_eq!(rest.height, 0);
+        assert_eq!(bottom, rect);
+
+        assert!(rect.contains(1, 2));
+        assert!(rect.contains(6, 11));
+        assert!(!rect.contains(7, 11));
+        assert!(!rect.contains(6, 12));
+        assert!(!rect.contains(0, 2));
+    }
+
+    #[test]
+    fn test_headless_terminal() {
+        use headless::{HeadlessTerminal, ScreenCell};
+
+        let terminal = HeadlessTerminal::new(8, 3);
+        terminal.mvaddstr(0, 0, "plain");
+        terminal.attron(Attrs::BOLD | Attrs::GREEN);
+        terminal.mvaddstr(1, 5, "clipped");
+        terminal.attroff(Attrs::BOLD);
+        terminal.mvaddch(2, 1, 'g');
+        terminal.attroff(Attrs::GREEN);
+        terminal.mvaddstr(-1, 0, "off screen");
+        terminal.mvchgat(0, 1, 2, Attrs::REVERSE);
+
+        assert_eq!(terminal.text(), "plain\n     cli\n g\n");
+        assert_eq!(
+            terminal.cell(1, 7),
+            ScreenCell {
+                ch: 'i',
+                attrs: Attrs::BOLD | Attrs::GREEN,
+            }
+        );
+        assert_eq!(terminal.cell(2, 1).attrs, Attrs::GREEN);
+        assert_eq!(terminal.cell(0, 0).attrs, Attrs::default());
+        assert_eq!(terminal.cell(0, 2).attrs, Attrs::REVERSE);
+        assert_eq!(terminal.cell(0, 2).ch, 'a');
+
+        terminal.push_keys(&[Key::Char('w'), Key::Enter]);
+        assert_eq!(terminal.poll_key(), Some(Key::Char('w')));
+        assert_eq!(terminal.poll_key(), Some(Key::Enter));
+        assert_eq!(terminal.poll_key(), None);
+
+        terminal.erase();
+        assert_eq!(terminal.text(), "\n\n\n");
+    }
+
+    #[test]
+    fn test_error_panel_fits_narrow_windows() {
+        use headless::HeadlessTerminal;
+
+        let terminal = HeadlessTerminal::new(24, 12);
+        terminal.push_keys(&[Key::Enter]);
+        show_error_panel(&terminal, "couldn't write recording /nonexistent/r.txt");
+
+        let expected_text = r#"
++----------------------+
+| ERROR                |
+|                      |
+| couldn't write       |
+| recording            |
+| /nonexistent/r.txt   |
+|                      |
+| Press any key to     |
+| continue             |
++----------------------+
+
+"#;
+        assert_eq!(terminal.text(), expected_text);
+    }
+
+    #[test]
+    fn test_rect_centered() {
+        let rect = Rect {
+            left: 0,
+            top: 0,
+            width: 80,
+            height: 24,
+        };
+        assert_eq!(
+            rect.centered(20, 4),
+            Rect {
+                left: 30,
+                top: 10,
+                width: 20,
+                height: 4,
+            }
+        );
+        assert!(rect.fits(80, 24));
+        assert!(!rect.fits(81, 24));
+    }
+}
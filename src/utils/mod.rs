@@ -1,2 +1,2 @@
+pub mod seeded_rng;
 pub mod str_utils;
-pub mod tui;
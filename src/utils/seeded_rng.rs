@@ -0,0 +1,53 @@
+use snm_rand_utils::range_rng::RangeRng;
+
+/// A small splitmix64 generator. All of its math is done in u64 so the same seed produces the same
+/// sequence of values on every platform, which lets a seed stand in for a whole generated board.
+pub struct SeededRangeRng {
+    state: u64,
+}
+
+impl SeededRangeRng {
+    pub fn new(seed: u64) -> Self {
+        SeededRangeRng { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+impl RangeRng<usize> for SeededRangeRng {
+    fn gen_range(&mut self, lower: usize, upper: usize) -> usize {
+        assert!(lower < upper);
+        let range = (upper - lower) as u64;
+        lower + (self.next_u64() % range) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_range_rng() {
+        let mut rng_a = SeededRangeRng::new(1234);
+        let mut rng_b = SeededRangeRng::new(1234);
+        let values_a: Vec<usize> = (0..100).map(|_| rng_a.gen_range(10, 20)).collect();
+        let values_b: Vec<usize> = (0..100).map(|_| rng_b.gen_range(10, 20)).collect();
+        assert_eq!(values_a, values_b);
+        assert!(values_a.iter().all(|v| (10..20).contains(v)));
+
+        // pin down the sequence so it can't drift between versions and break shared seeds
+        let mut rng = SeededRangeRng::new(0);
+        assert_eq!(rng.next_u64(), 0xE220_A839_7B1D_CDAF);
+        assert_eq!(rng.next_u64(), 0x6E78_9E6A_A1B9_65F4);
+
+        let mut rng_c = SeededRangeRng::new(4321);
+        let values_c: Vec<usize> = (0..100).map(|_| rng_c.gen_range(10, 20)).collect();
+        assert_ne!(values_a, values_c);
+    }
+}
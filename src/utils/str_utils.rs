@@ -1,8 +1,9 @@
+/// The number of positions where the two words have the same letter, i.e. the game's likeness
 pub fn matching_char_count_ignore_case(a: &str, b: &str) -> usize {
     assert_eq!(a.len(), b.len());
 
     fn chars_eq_ignore_case((a, b): &(char, char)) -> bool {
-        a.to_ascii_lowercase() == b.to_ascii_lowercase()
+        a.eq_ignore_ascii_case(b)
     }
 
     a.chars()
@@ -11,11 +12,39 @@ pub fn matching_char_count_ignore_case(a: &str, b: &str) -> usize {
         .count()
 }
 
+/// The number of positions where the two words differ
 pub fn hamming_dist_ignore_case(a: &str, b: &str) -> usize {
     assert_eq!(a.len(), b.len());
     a.len() - matching_char_count_ignore_case(a, b)
 }
 
+/// Break text into lines no longer than max_width, splitting on whitespace where possible
+pub fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current_line = String::new();
+    for word in text.split_whitespace() {
+        if !current_line.is_empty() && current_line.len() + 1 + word.len() > max_width {
+            lines.push(std::mem::take(&mut current_line));
+        }
+
+        if !current_line.is_empty() {
+            current_line.push(' ');
+        }
+        current_line.push_str(word);
+
+        // words which can't fit on a line by themselves get split across lines
+        while current_line.len() > max_width {
+            let remainder = current_line.split_off(max_width);
+            lines.push(std::mem::replace(&mut current_line, remainder));
+        }
+    }
+
+    if !current_line.is_empty() {
+        lines.push(current_line);
+    }
+    lines
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -33,4 +62,17 @@ mod tests {
         assert_eq!(hamming_dist_ignore_case("apple", "loodo"), 5);
         assert_eq!(hamming_dist_ignore_case("upper", "APpLe"), 3);
     }
+
+    #[test]
+    fn test_wrap_text() {
+        assert_eq!(
+            wrap_text("the quick brown fox", 10),
+            vec!["the quick", "brown fox"]
+        );
+        assert_eq!(
+            wrap_text("abcdefghij klm", 4),
+            vec!["abcd", "efgh", "ij", "klm"]
+        );
+        assert!(wrap_text("", 10).is_empty());
+    }
 }